@@ -3,7 +3,7 @@ extern crate criterion;
 use ark_std::rand::Rng;
 
 use ark_crypto_primitives::crh::monolith::fields::goldilocks::Fr as F64;
-use ark_crypto_primitives::crh::monolith::permute::{self, MonolithPermute};
+use ark_crypto_primitives::crh::monolith::permute::{MonolithPermute, BATCH_LANES};
 use ark_crypto_primitives::crh::monolith::CRH64;
 use ark_crypto_primitives::crh::CRHScheme;
 use ark_std::UniformRand;
@@ -44,8 +44,60 @@ fn monolith_permute(c: &mut Criterion) {
     let rng = &mut ark_std::test_rng();
     let params = CRH64::<12>::setup(rng).unwrap();
     let mut input: [F64; 12] = rng.gen();
+    let reference_params = params.clone();
+    let mut reference_input = input;
     c.bench_function("Monolith Permute", move |b| {
-        b.iter(|| MonolithPermute::<12>::permute(&mut input, &params))
+        b.iter(|| MonolithPermute::<F64, 12>::permute(&mut input, &params).unwrap())
+    });
+    // The same permutation evaluated with field arithmetic in every step, for comparison with the
+    // lazily reduced integer path `permute` takes for Goldilocks.
+    c.bench_function("Monolith Permute (field arithmetic)", move |b| {
+        b.iter(|| {
+            MonolithPermute::<F64, 12>::permute_reference(&mut reference_input, &reference_params)
+        })
+    });
+}
+fn monolith_permute_batch(c: &mut Criterion) {
+    let rng = &mut ark_std::test_rng();
+    let params = CRH64::<12>::setup(rng).unwrap();
+    let mut states: Vec<[F64; 12]> = (0..1024).map(|_| rng.gen()).collect();
+    let batch_params = params.clone();
+    let mut batch_states = states.clone();
+    c.bench_function("Monolith Permute x1024", move |b| {
+        b.iter(|| {
+            for state in states.iter_mut() {
+                MonolithPermute::<F64, 12>::permute(state, &params).unwrap();
+            }
+        })
+    });
+    c.bench_function("Monolith Permute Batch x1024", move |b| {
+        b.iter(|| {
+            MonolithPermute::<F64, 12>::permute_batch(&mut batch_states, &batch_params).unwrap()
+        })
+    });
+}
+fn monolith_concrete_lanes(c: &mut Criterion) {
+    let rng = &mut ark_std::test_rng();
+    let mut lanes: [[u64; BATCH_LANES]; 12] = rng.gen();
+    let mut gather_lanes = lanes;
+    let round_constant: [u64; 12] = core::array::from_fn(|_| rng.gen::<u32>() as u64);
+    c.bench_function("Monolith Concrete Layer (lane-wise)", move |b| {
+        b.iter(|| MonolithPermute::<F64, 12>::concrete_lanes(&mut lanes, &round_constant))
+    });
+    // The same layer with every lane gathered into a state for the FFT-based multiplication.
+    c.bench_function("Monolith Concrete Layer (per-lane gather)", move |b| {
+        b.iter(|| {
+            for lane in 0..BATCH_LANES {
+                let mut state = [0u128; 12];
+                for (s, input) in state.iter_mut().zip(gather_lanes.iter()) {
+                    *s = input[lane] as u128;
+                }
+                MonolithPermute::<F64, 12>::concrete_wrc_u128(&mut state, &round_constant);
+                for (s, output) in state.iter().zip(gather_lanes.iter_mut()) {
+                    output[lane] = *s as u64;
+                }
+            }
+        })
     });
 }
 criterion_group! {
@@ -56,7 +108,7 @@ criterion_group! {
 criterion_group! {
     name = monolith_perm;
     config = Criterion::default().sample_size(10);
-    targets = monolith_permute
+    targets = monolith_permute, monolith_permute_batch, monolith_concrete_lanes
 }
 
 criterion_main!(monolith_perm, monolith_hash);
@@ -0,0 +1,93 @@
+//! Constraint counts of the Monolith-64 gadgets, natively over Goldilocks and emulated over the
+//! BLS12-381 scalar field. Run with `cargo bench --bench monolith_constraints`.
+use ark_crypto_primitives::crh::monolith::constraints::{CRHGadget, CRHParametersVar};
+use ark_crypto_primitives::crh::monolith::emulated::EmulatedCRHGadget;
+use ark_crypto_primitives::crh::monolith::fields::goldilocks::Fr as F64;
+use ark_crypto_primitives::crh::monolith::permute::constraints::MonolithPermuteVar;
+use ark_crypto_primitives::crh::monolith::permute::emulated::{EmulatedMonolithPermuteVar, F64Var};
+use ark_crypto_primitives::crh::monolith::CRH64;
+use ark_crypto_primitives::crh::{CRHScheme, CRHSchemeGadget};
+use ark_ed_on_bls12_381::Fq as BlsFr;
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+use ark_std::UniformRand;
+
+/// Number of field elements hashed by the CRH rows.
+const HASH_INPUT_LEN: usize = 8;
+
+fn report<CF: PrimeField>(name: &str, synthesize: impl FnOnce(ConstraintSystemRef<CF>)) {
+    let cs = ConstraintSystem::<CF>::new_ref();
+    let start = std::time::Instant::now();
+    synthesize(cs.clone());
+    let elapsed = start.elapsed();
+    assert!(cs.is_satisfied().unwrap());
+    println!(
+        "{name:<40} {:>9} constraints {:>9} witnesses {:>10.2?}",
+        cs.num_constraints(),
+        cs.num_witness_variables(),
+        elapsed
+    );
+}
+
+fn native_permute<const T: usize>() {
+    let rng = &mut ark_std::test_rng();
+    let params = CRH64::<T>::setup(rng).unwrap();
+    report::<F64>(&format!("permutation, width {T}, native"), |cs| {
+        let mut state = (0..T)
+            .map(|_| FpVar::new_witness(cs.clone(), || Ok(F64::rand(rng))).unwrap())
+            .collect::<Vec<_>>();
+        MonolithPermuteVar::<F64, T>::default()
+            .permute(&mut state, &params)
+            .unwrap();
+    });
+}
+
+fn emulated_permute<const T: usize>() {
+    let rng = &mut ark_std::test_rng();
+    let params = CRH64::<T>::setup(rng).unwrap();
+    report::<BlsFr>(&format!("permutation, width {T}, over BLS12-381"), |cs| {
+        let mut state = (0..T)
+            .map(|_| F64Var::new_witness(cs.clone(), || Ok(F64::rand(rng))).unwrap())
+            .collect::<Vec<_>>();
+        EmulatedMonolithPermuteVar::<BlsFr, T>::default()
+            .permute(&mut state, &params)
+            .unwrap();
+    });
+}
+
+fn native_hash<const T: usize>() {
+    let rng = &mut ark_std::test_rng();
+    let params = CRH64::<T>::setup(rng).unwrap();
+    report::<F64>(&format!("CRH64, width {T}, native"), |cs| {
+        let params_var = CRHParametersVar::new_constant(cs.clone(), params).unwrap();
+        let input = (0..HASH_INPUT_LEN)
+            .map(|_| FpVar::new_witness(cs.clone(), || Ok(F64::rand(rng))).unwrap())
+            .collect::<Vec<_>>();
+        CRHGadget::<T>::evaluate(&params_var, &input).unwrap();
+    });
+}
+
+fn emulated_hash<const T: usize>() {
+    let rng = &mut ark_std::test_rng();
+    let params = CRH64::<T>::setup(rng).unwrap();
+    report::<BlsFr>(&format!("CRH64, width {T}, over BLS12-381"), |cs| {
+        let params_var = CRHParametersVar::new_constant(cs.clone(), params).unwrap();
+        let input = (0..HASH_INPUT_LEN)
+            .map(|_| F64Var::new_witness(cs.clone(), || Ok(F64::rand(rng))).unwrap())
+            .collect::<Vec<_>>();
+        EmulatedCRHGadget::<BlsFr, T>::evaluate(&params_var, &input).unwrap();
+    });
+}
+
+fn main() {
+    native_permute::<8>();
+    native_permute::<12>();
+    emulated_permute::<8>();
+    emulated_permute::<12>();
+    native_hash::<8>();
+    native_hash::<12>();
+    emulated_hash::<8>();
+    emulated_hash::<12>();
+}
@@ -1,7 +1,7 @@
 use ark_ff::Field;
 use core::fmt::Debug;
 
-use crate::crh::{CRHScheme, TwoToOneCRHScheme};
+use crate::crh::{CRHScheme, ThreeToOneCRHScheme, TwoToOneCRHScheme};
 use ark_relations::r1cs::SynthesisError;
 
 use ark_r1cs_std::prelude::*;
@@ -49,3 +49,31 @@ pub trait TwoToOneCRHSchemeGadget<H: TwoToOneCRHScheme, ConstraintF: Field>: Siz
         right_input: &Self::OutputVar,
     ) -> Result<Self::OutputVar, SynthesisError>;
 }
+
+pub trait ThreeToOneCRHSchemeGadget<H: ThreeToOneCRHScheme, ConstraintF: Field>: Sized {
+    type InputVar: ?Sized;
+    type OutputVar: EqGadget<ConstraintF>
+        + ToBytesGadget<ConstraintF>
+        + CondSelectGadget<ConstraintF>
+        + AllocVar<H::Output, ConstraintF>
+        + R1CSVar<ConstraintF>
+        + Debug
+        + Clone
+        + Sized;
+
+    type ParametersVar: AllocVar<H::Parameters, ConstraintF> + Clone;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        center_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError>;
+
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        center_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError>;
+}
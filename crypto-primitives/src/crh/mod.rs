@@ -67,3 +67,34 @@ pub trait TwoToOneCRHScheme {
         right_input: T,
     ) -> Result<Self::Output, Error>;
 }
+
+/// CRH compressing three inputs into one, e.g. for the inner nodes of a ternary Merkle tree.
+pub trait ThreeToOneCRHScheme {
+    /// Raw Input type of ThreeToOneCRH
+    type Input: ?Sized;
+    /// Raw Output type of ThreeToOneCRH
+    type Output: Clone
+        + Eq
+        + core::fmt::Debug
+        + Hash
+        + Default
+        + CanonicalSerialize
+        + CanonicalDeserialize;
+    type Parameters: Clone + CanonicalSerialize + CanonicalDeserialize + Sync;
+
+    fn setup<R: Rng>(r: &mut R) -> Result<Self::Parameters, Error>;
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        center_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error>;
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        center_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error>;
+}
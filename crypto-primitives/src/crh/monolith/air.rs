@@ -0,0 +1,541 @@
+//! An algebraic intermediate representation (AIR) of the Monolith permutation, for proving it
+//! inside a STARK.
+//!
+//! [`MonolithAir::generate_trace`] turns a permutation call into an [`ExecutionTrace`] with one
+//! row per round. Every row holds the state entering the round, the bit and limb decompositions
+//! of the elements that go through a bar, the bar outputs, the outputs of the bricks and the
+//! outputs of the MDS layer with the round constants, at the columns given by [`TraceLayout`].
+//! [`MonolithAir::transition_constraints`] lists the polynomials a valid trace makes vanish, and
+//! [`MonolithAir::check_trace`] evaluates all of them on a trace.
+
+use crate::crh::monolith::fields::{modulus_high_ones, s_box, MonolithField, SBoxTaps};
+use crate::crh::monolith::permute::MonolithPermute;
+use crate::crh::monolith::MonolithParams;
+use crate::Error;
+use ark_ff::{BigInteger, Field};
+#[cfg(not(feature = "std"))]
+use ark_std::{boxed::Box, vec::Vec};
+
+/// Column indices of a Monolith [`ExecutionTrace`] row.
+///
+/// A row starts with the `state_size` elements of the state entering the round. For each of the
+/// first `bars` elements follow its canonical bits (least significant first), its limbs, the
+/// auxiliary column of its range check and its bar output. The row ends with the outputs of the
+/// bricks and of the MDS layer, `state_size` elements each.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceLayout {
+    state_size: usize,
+    bars: usize,
+    element_bits: usize,
+    limbs: usize,
+}
+
+impl TraceLayout {
+    /// The layout of the trace of the permutation over `F` with `params`.
+    pub fn new<F: MonolithField>(params: &MonolithParams<F>) -> Self {
+        Self {
+            state_size: params.state_size(),
+            bars: params.bar_per_round().into(),
+            element_bits: F::LIMB_SIZES.iter().sum::<u32>() as usize,
+            limbs: F::LIMB_SIZES.len(),
+        }
+    }
+
+    /// Number of columns of a row.
+    pub fn width(&self) -> usize {
+        self.mds(0) + self.state_size
+    }
+
+    /// Element `i` of the state entering the round. For `i < bars` this is a bar input.
+    pub fn state(&self, i: usize) -> usize {
+        i
+    }
+
+    fn bar_columns(&self) -> usize {
+        self.element_bits + self.limbs + 2
+    }
+
+    fn bar_offset(&self, j: usize) -> usize {
+        self.state_size + j * self.bar_columns()
+    }
+
+    /// Bit `i` of the canonical representative of bar input `j`.
+    pub fn bit(&self, j: usize, i: usize) -> usize {
+        self.bar_offset(j) + i
+    }
+
+    /// Limb `k` of bar input `j`, least significant first.
+    pub fn limb(&self, j: usize, k: usize) -> usize {
+        self.bar_offset(j) + self.element_bits + k
+    }
+
+    /// The auxiliary column of the range check on the bits of bar input `j`.
+    pub fn canonical_inverse(&self, j: usize) -> usize {
+        self.bar_offset(j) + self.element_bits + self.limbs
+    }
+
+    /// The bar output of bar input `j`.
+    pub fn bar_output(&self, j: usize) -> usize {
+        self.canonical_inverse(j) + 1
+    }
+
+    /// Element `i` of the output of the bricks.
+    pub fn brick(&self, i: usize) -> usize {
+        self.bar_offset(self.bars) + i
+    }
+
+    /// Element `i` of the output of the MDS layer with the round constants, the state leaving
+    /// the round.
+    pub fn mds(&self, i: usize) -> usize {
+        self.brick(self.state_size) + i
+    }
+}
+
+/// The execution trace of one permutation call: the permutation input and one row per round,
+/// laid out as in [`TraceLayout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionTrace<F: Field> {
+    /// The permutation input. The state entering the first round is the MDS layer applied to it.
+    pub input: Vec<F>,
+    /// One row per round.
+    pub rows: Vec<Vec<F>>,
+}
+
+impl<F: Field> ExecutionTrace<F> {
+    /// The permutation output, the MDS outputs of the last round.
+    pub fn output(&self, layout: &TraceLayout) -> &[F] {
+        self.rows
+            .last()
+            .and_then(|row| row.get(layout.mds(0)..layout.width()))
+            .unwrap_or(&[])
+    }
+}
+
+/// A polynomial in the current row, the next row and the round constants of the current round.
+type Polynomial<F> = dyn Fn(&[F], &[F], &[F]) -> F + Send + Sync;
+
+/// A transition constraint: a polynomial in the cells of the current row, the cells of the next
+/// row and the round constants of the current round, which vanishes on every row of a valid
+/// trace.
+pub struct TransitionConstraint<F: Field> {
+    /// Degree of the polynomial in the trace cells.
+    pub degree: usize,
+    /// Whether the polynomial reads the next row. Such constraints do not apply to the last row.
+    pub uses_next_row: bool,
+    polynomial: Box<Polynomial<F>>,
+}
+
+impl<F: Field> TransitionConstraint<F> {
+    fn new(
+        degree: usize,
+        uses_next_row: bool,
+        polynomial: impl Fn(&[F], &[F], &[F]) -> F + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            degree,
+            uses_next_row,
+            polynomial: Box::new(polynomial),
+        }
+    }
+
+    /// Evaluates the polynomial on the `current` row, the `next` row and the `round_constants`
+    /// of the current round. `next` is not read unless [`Self::uses_next_row`] is set.
+    pub fn evaluate(&self, current: &[F], next: &[F], round_constants: &[F]) -> F {
+        (self.polynomial)(current, next, round_constants)
+    }
+}
+
+/// Why [`MonolithAir::check_trace`] rejected a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceViolation {
+    /// The trace does not have the number of input elements, rows or columns of the AIR.
+    Shape,
+    /// The first row does not start from the MDS layer applied to the input.
+    Input,
+    /// Constraint `constraint` of [`MonolithAir::transition_constraints`] does not vanish on row
+    /// `row`.
+    Transition { row: usize, constraint: usize },
+}
+
+/// The AIR of [`MonolithPermute`] over `F` with a state of `T` elements.
+///
+/// The bits of a bar input are range checked like in the circuit: with `z` the number of high
+/// bits of `p - 1` that are ones minus the sum of the same bits of the input, the auxiliary
+/// column holds the inverse of `z`, or zero, and `e = 1 - z * inverse` must satisfy `z * e = 0`
+/// and `e * low = 0`.
+///
+/// The S-box output bits are polynomials of degree 4 in the input bits, so no constraint has a
+/// degree above 4.
+pub struct MonolithAir<F: MonolithField, const T: usize> {
+    params: MonolithParams<F>,
+    layout: TraceLayout,
+    mds: Vec<Vec<F>>,
+    /// Number of high bits of `p - 1` that are all ones.
+    high_ones: usize,
+}
+
+impl<F: MonolithField, const T: usize> MonolithAir<F, T> {
+    /// The AIR of the permutation with `params`, which must be for a state of `T` elements. Fails
+    /// as well if `p - 1` is not a run of ones above zeros, which the range check relies on.
+    pub fn new(params: &MonolithParams<F>) -> Result<Self, Error> {
+        let () = MonolithPermute::<F, T>::WIDTH_CHECK;
+        params.check_state_size(T)?;
+        let layout = TraceLayout::new(params);
+        let high_ones = modulus_high_ones::<F>().ok_or(Error::InsecureParameters(
+            "the modulus minus one has to be a run of ones above zeros",
+        ))?;
+        Ok(Self {
+            params: params.clone(),
+            layout,
+            mds: F::mds_matrix(T),
+            high_ones,
+        })
+    }
+
+    /// The column layout of the trace rows.
+    pub fn layout(&self) -> &TraceLayout {
+        &self.layout
+    }
+
+    /// Runs the permutation on `input`, which holds exactly `T` elements, and records its
+    /// execution trace.
+    pub fn generate_trace(&self, input: &[F]) -> Result<ExecutionTrace<F>, Error> {
+        if input.len() != T {
+            return Err(Error::IncorrectInputLength(input.len()));
+        }
+        let layout = &self.layout;
+        let mut state = [F::zero(); T];
+        state.copy_from_slice(input);
+        MonolithPermute::<F, T>::concrete(&mut state);
+
+        let mut rows = Vec::with_capacity(self.params.round_constants.len());
+        for rc in self.params.round_constants.iter() {
+            let mut row = vec![F::zero(); layout.width()];
+            row[..T].copy_from_slice(&state);
+            for (j, element) in state.iter().enumerate().take(layout.bars) {
+                let bits = element.into_bigint().to_bits_le();
+                self.fill_bar(&mut row, j, &bits[..layout.element_bits]);
+            }
+            MonolithPermute::<F, T>::bars(&mut state, &self.params);
+            MonolithPermute::<F, T>::bricks(&mut state);
+            row[layout.brick(0)..layout.mds(0)].copy_from_slice(&state);
+            MonolithPermute::<F, T>::concrete_wrc(&mut state, rc);
+            row[layout.mds(0)..].copy_from_slice(&state);
+            rows.push(row);
+        }
+        Ok(ExecutionTrace {
+            input: input.to_vec(),
+            rows,
+        })
+    }
+
+    /// Fills the bit, limb, range check and bar output columns of bar input `j` in `row` from
+    /// the little-endian `bits` of its representative.
+    fn fill_bar(&self, row: &mut [F], j: usize, bits: &[bool]) {
+        let layout = &self.layout;
+        let mut offset = 0;
+        let mut bar_output = F::zero();
+        for (k, size) in F::LIMB_SIZES.iter().enumerate() {
+            let limb_bits = &bits[offset..offset + *size as usize];
+            let limb = limb_bits
+                .iter()
+                .rev()
+                .fold(0u8, |acc, bit| (acc << 1) | *bit as u8);
+            row[layout.limb(j, k)] = F::from(limb);
+            bar_output += F::from(s_box(limb, *size)) * F::from(2u64).pow([offset as u64]);
+            offset += *size as usize;
+        }
+        for (i, bit) in bits.iter().enumerate() {
+            row[layout.bit(j, i)] = F::from(*bit);
+        }
+        let low = layout.element_bits - self.high_ones;
+        let high_sum = bits[low..].iter().filter(|bit| **bit).count();
+        row[layout.canonical_inverse(j)] = F::from((self.high_ones - high_sum) as u64)
+            .inverse()
+            .unwrap_or_default();
+        row[layout.bar_output(j)] = bar_output;
+    }
+
+    /// The transition constraints, in the order [`Self::check_trace`] evaluates them.
+    ///
+    /// For every bar input: its bits are boolean, its limbs and the input itself are recomposed
+    /// from them, the bits are canonical and the bar output is the S-boxes applied to the limbs.
+    /// Then every brick output, every MDS output and, between rows, every element of the next
+    /// state equals the MDS output it comes from.
+    pub fn transition_constraints(&self) -> Vec<TransitionConstraint<F>> {
+        let layout = self.layout;
+        let mut constraints = Vec::new();
+        for j in 0..layout.bars {
+            for i in 0..layout.element_bits {
+                let bit = layout.bit(j, i);
+                constraints.push(TransitionConstraint::new(2, false, move |cur, _, _| {
+                    cur[bit] * (cur[bit] - F::one())
+                }));
+            }
+            let mut offset = 0;
+            for (k, size) in F::LIMB_SIZES.iter().enumerate() {
+                let bits = layout.bit(j, offset)..layout.bit(j, offset + *size as usize);
+                let limb = layout.limb(j, k);
+                constraints.push(TransitionConstraint::new(1, false, move |cur, _, _| {
+                    cur[limb] - recompose(&cur[bits.clone()])
+                }));
+                offset += *size as usize;
+            }
+            constraints.push(TransitionConstraint::new(1, false, move |cur, _, _| {
+                let mut power = F::one();
+                let mut element = F::zero();
+                for (k, size) in F::LIMB_SIZES.iter().enumerate() {
+                    element += cur[layout.limb(j, k)] * power;
+                    power *= F::from(2u64).pow([u64::from(*size)]);
+                }
+                cur[layout.state(j)] - element
+            }));
+
+            let low = layout.element_bits - self.high_ones;
+            let high_ones = F::from(self.high_ones as u64);
+            let high = layout.bit(j, low)..layout.bit(j, layout.element_bits);
+            let inverse = layout.canonical_inverse(j);
+            let high_not_ones = move |cur: &[F]| high_ones - cur[high.clone()].iter().sum::<F>();
+            let not_all_ones = high_not_ones.clone();
+            constraints.push(TransitionConstraint::new(3, false, move |cur, _, _| {
+                let z = not_all_ones(cur);
+                z * (F::one() - z * cur[inverse])
+            }));
+            let low_bits = layout.bit(j, 0)..layout.bit(j, low);
+            constraints.push(TransitionConstraint::new(3, false, move |cur, _, _| {
+                let z = high_not_ones(cur);
+                (F::one() - z * cur[inverse]) * recompose(&cur[low_bits.clone()])
+            }));
+
+            constraints.push(TransitionConstraint::new(4, false, move |cur, _, _| {
+                let mut output = F::zero();
+                let mut offset = 0;
+                let mut power = F::one();
+                for size in F::LIMB_SIZES {
+                    let bits = &cur[layout.bit(j, offset)..layout.bit(j, offset + *size as usize)];
+                    output += s_box_polynomial(bits) * power;
+                    power *= F::from(2u64).pow([u64::from(*size)]);
+                    offset += *size as usize;
+                }
+                cur[layout.bar_output(j)] - output
+            }));
+        }
+
+        // The bricks read the bar outputs in place of the bar inputs.
+        let barred = move |cur: &[F], i: usize| {
+            if i < layout.bars {
+                cur[layout.bar_output(i)]
+            } else {
+                cur[layout.state(i)]
+            }
+        };
+        for i in 0..T {
+            constraints.push(TransitionConstraint::new(2, false, move |cur, _, _| {
+                let mut brick = barred(cur, i);
+                if i > 0 {
+                    brick += barred(cur, i - 1).square();
+                }
+                cur[layout.brick(i)] - brick
+            }));
+        }
+        for (i, row) in self.mds.iter().enumerate() {
+            let row = row.clone();
+            constraints.push(TransitionConstraint::new(1, false, move |cur, _, rc| {
+                let product: F = row
+                    .iter()
+                    .enumerate()
+                    .map(|(k, m)| *m * cur[layout.brick(k)])
+                    .sum();
+                cur[layout.mds(i)] - product - rc[i]
+            }));
+        }
+        for i in 0..T {
+            constraints.push(TransitionConstraint::new(1, true, move |cur, next, _| {
+                next[layout.state(i)] - cur[layout.mds(i)]
+            }));
+        }
+        constraints
+    }
+
+    /// Checks that `trace` is a valid execution trace of the permutation: it has the right
+    /// shape, its first row starts from the MDS layer applied to its input, and every transition
+    /// constraint vanishes on every row it applies to. Reports the first violation found.
+    pub fn check_trace(&self, trace: &ExecutionTrace<F>) -> Result<(), TraceViolation> {
+        let width = self.layout.width();
+        if trace.input.len() != T
+            || trace.rows.len() != self.params.round_constants.len()
+            || trace.rows.iter().any(|row| row.len() != width)
+        {
+            return Err(TraceViolation::Shape);
+        }
+        let mut state = [F::zero(); T];
+        state.copy_from_slice(&trace.input);
+        MonolithPermute::<F, T>::concrete(&mut state);
+        if trace.rows.first().is_some_and(|row| row[..T] != state) {
+            return Err(TraceViolation::Input);
+        }
+
+        let constraints = self.transition_constraints();
+        for (r, (row, rc)) in trace
+            .rows
+            .iter()
+            .zip(self.params.round_constants.iter())
+            .enumerate()
+        {
+            let next = trace
+                .rows
+                .get(r + 1)
+                .map_or(&[][..], |next| next.as_slice());
+            for (c, constraint) in constraints.iter().enumerate() {
+                if constraint.uses_next_row && next.is_empty() {
+                    continue;
+                }
+                if !constraint.evaluate(row, next, rc).is_zero() {
+                    return Err(TraceViolation::Transition {
+                        row: r,
+                        constraint: c,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The integer with little-endian `bits`, as a linear combination of them.
+fn recompose<F: Field>(bits: &[F]) -> F {
+    bits.iter()
+        .rev()
+        .fold(F::zero(), |acc, bit| acc.double() + bit)
+}
+
+/// The S-box on a limb with little-endian `bits` as a polynomial in them, which agrees with
+/// [`s_box`] on boolean inputs: `a ^ b` is `a + b - 2ab` and `!a` is `1 - a`.
+fn s_box_polynomial<F: Field>(bits: &[F]) -> F {
+    let out: Vec<F> = (0..bits.len())
+        .map(|i| {
+            let taps = SBoxTaps::new(bits.len(), i);
+            let mut and = (F::one() - bits[taps.negated]) * bits[taps.and];
+            if let Some(k) = taps.and_8 {
+                and *= bits[k];
+            }
+            bits[taps.xor] + and - (bits[taps.xor] * and).double()
+        })
+        .collect();
+    recompose(&out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crh::monolith::fields::goldilocks::Fr as F64;
+    use crate::crh::monolith::fields::mersenne::Fr as F31;
+    use crate::crh::monolith::{CRH31, CRH64};
+    use crate::crh::CRHScheme;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    fn check_air<F: MonolithField, const T: usize>(params: &MonolithParams<F>) {
+        let mut rng = test_rng();
+        let air = MonolithAir::<F, T>::new(params).unwrap();
+        let layout = *air.layout();
+        let constraints = air.transition_constraints();
+        assert!(constraints.iter().all(|c| c.degree <= 4));
+
+        let mut inputs: Vec<Vec<F>> = (0..3)
+            .map(|_| (0..T).map(|_| F::rand(&mut rng)).collect())
+            .collect();
+        inputs.push(vec![F::zero(); T]);
+        inputs.push(vec![-F::one(); T]);
+        for input in inputs {
+            let trace = air.generate_trace(&input).unwrap();
+            let mut output = input.clone();
+            MonolithPermute::<F, T>::permute(&mut output, params).unwrap();
+            assert_eq!(trace.output(&layout), output.as_slice());
+            assert_eq!(trace.rows.len(), params.round_constants.len());
+            assert_eq!(air.check_trace(&trace), Ok(()));
+
+            // Changing any single cell breaks the trace.
+            for _ in 0..20 {
+                let mut tampered = trace.clone();
+                let r = usize::rand(&mut rng) % tampered.rows.len();
+                let c = usize::rand(&mut rng) % layout.width();
+                tampered.rows[r][c] += F::one();
+                assert!(air.check_trace(&tampered).is_err(), "row {r}, column {c}");
+            }
+            let mut tampered = trace.clone();
+            tampered.input[0] += F::one();
+            assert_eq!(air.check_trace(&tampered), Err(TraceViolation::Input));
+            let mut tampered = trace.clone();
+            tampered.rows.pop();
+            assert_eq!(air.check_trace(&tampered), Err(TraceViolation::Shape));
+        }
+        assert!(air.generate_trace(&[F::zero(); 3]).is_err());
+    }
+
+    #[test]
+    fn trace_consistency() {
+        let mut rng = test_rng();
+        check_air::<F64, 8>(&CRH64::<8>::setup(&mut rng).unwrap());
+        check_air::<F64, 12>(&CRH64::<12>::setup(&mut rng).unwrap());
+        check_air::<F31, 16>(&CRH31::<16>::setup(&mut rng).unwrap());
+    }
+
+    #[test]
+    fn wrong_width_parameters() {
+        let mut rng = test_rng();
+        let params = CRH64::<12>::setup(&mut rng).unwrap();
+        assert!(MonolithAir::<F64, 8>::new(&params).is_err());
+    }
+
+    /// A bar input decomposed into the bits of `x + p` instead of `x` satisfies every
+    /// recomposition, but the range check rejects it.
+    #[test]
+    fn non_canonical_bits() {
+        const P: u64 = 0xFFFF_FFFF_0000_0001;
+        let mut rng = test_rng();
+        let params = CRH64::<12>::setup(&mut rng).unwrap();
+        let air = MonolithAir::<F64, 12>::new(&params).unwrap();
+        let layout = *air.layout();
+
+        // An input whose first state element is small, so that adding `p` does not overflow.
+        let mut target = [F64::from(5u64); 12];
+        target[1] = F64::rand(&mut rng);
+        let inverse = F64::mds_inverse_matrix(12);
+        let input: Vec<F64> = inverse
+            .iter()
+            .map(|row| row.iter().zip(target.iter()).map(|(m, x)| *m * x).sum())
+            .collect();
+        let mut trace = air.generate_trace(&input).unwrap();
+        assert_eq!(trace.rows[0][layout.state(0)], F64::from(5u64));
+        assert_eq!(air.check_trace(&trace), Ok(()));
+
+        let bits: Vec<bool> = (0..64).map(|i| ((5 + P) >> i) & 1 == 1).collect();
+        air.fill_bar(&mut trace.rows[0], 0, &bits);
+        let violation = air.check_trace(&trace).unwrap_err();
+        // The constraints of bar input 0 start with 64 on the bits, 8 on the limbs, 1 on the
+        // recomposition and 2 for the range check, the second of which fails: the high bits
+        // are all ones and the low ones are not zero.
+        assert_eq!(
+            violation,
+            TraceViolation::Transition {
+                row: 0,
+                constraint: 64 + 8 + 1 + 1
+            }
+        );
+    }
+
+    #[test]
+    fn s_box_polynomial_matches() {
+        for bits in [7u32, 8] {
+            for limb in 0u8..=((1u16 << bits) - 1) as u8 {
+                let limb_bits: Vec<F64> = (0..bits).map(|i| F64::from((limb >> i) & 1)).collect();
+                assert_eq!(
+                    s_box_polynomial(&limb_bits),
+                    F64::from(s_box(limb, bits)),
+                    "limb {limb} of {bits} bits"
+                );
+            }
+        }
+    }
+}
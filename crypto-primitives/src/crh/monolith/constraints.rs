@@ -1,209 +1,409 @@
+use ark_ff::PrimeField;
 use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
 use ark_r1cs_std::boolean::Boolean;
 use ark_r1cs_std::eq::EqGadget;
 use ark_r1cs_std::fields::fp::FpVar;
-use ark_r1cs_std::prelude::{ToBytesGadget, UInt8};
+use ark_r1cs_std::prelude::{ToBitsGadget, ToBytesGadget, UInt8};
 use ark_r1cs_std::select::CondSelectGadget;
 use ark_r1cs_std::R1CSVar;
-use ark_relations::r1cs::ConstraintSystemRef;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
 use core::marker::PhantomData;
-use core::usize;
 
 use crate::crh::monolith::fields::goldilocks::Fr as FP64;
+use crate::crh::monolith::fields::MonolithField;
+use crate::crh::monolith::MonolithDigest;
 use crate::crh::monolith::MonolithParams;
+use crate::crh::monolith::ThreeToOneCrhScheme64;
 use crate::crh::monolith::TwoToOneCrhScheme64;
 use crate::crh::monolith::CRH64;
+use crate::crh::monolith::{
+    FixedLengthCRH64, MonolithBytes, BYTES_PER_ELEMENT, FIXED_LENGTH_DOMAIN, VARIABLE_LENGTH_DOMAIN,
+};
 use crate::crh::CRHScheme;
 use crate::crh::CRHSchemeGadget;
+use crate::crh::ThreeToOneCRHScheme;
+use crate::crh::ThreeToOneCRHSchemeGadget;
 use crate::crh::TwoToOneCRHScheme;
 use crate::crh::TwoToOneCRHSchemeGadget;
+use crate::sponge::constraints::AbsorbGadget;
 use crate::sponge::constraints::CryptographicSpongeVar;
 use crate::sponge::generic::constraints::MonolithSpongeVar;
 use crate::sponge::generic::generic_sponge::SpongeConfig;
 
 use super::permute::constraints::MonolithPermuteVar;
-// #[derive(Debug, Clone)]
-// struct VecFPVar(Vec<FpVar<FP64>>);
-// impl AllocVar<Vec<FP64>, FP64> for VecFPVar {
-//     fn new_variable<T: Borrow<Vec<FP64>>>(
-//         cs: impl Into<ark_relations::r1cs::Namespace<FP64>>,
-//         f: impl FnOnce() -> Result<T, ark_relations::r1cs::SynthesisError>,
-//         mode: AllocationMode,
-//     ) -> Result<Self, ark_relations::r1cs::SynthesisError> {
-//         let ns = cs.into();
-//         let cs = ns.cs();
-//
-//         let values = f()?.borrow().clone();
-//
-//         let mut vars = Vec::new();
-//         for val in values {
-//             let var = FpVar::new_variable(cs.clone(), || Ok(val), mode)?;
-//             vars.push(var);
-//         }
-//
-//         Ok(VecFPVar(vars))
-//     }
-// }
-// impl CondSelectGadget<FP64> for VecFPVar {
-//     fn conditionally_select(
-//         cond: &Boolean<FP64>,
-//         true_value: &Self,
-//         false_value: &Self,
-//     ) -> Result<Self, ark_relations::r1cs::SynthesisError> {
-//         // Ensure the lengths of both vectors are equal
-//         assert_eq!(true_value.0.len(), false_value.0.len());
-//
-//         let mut selected_vars = Vec::with_capacity(true_value.0.len());
-//         for (true_var, false_var) in true_value.0.iter().zip(false_value.0.iter()) {
-//             // Perform element-wise conditional selection
-//             let selected_var = FpVar::conditionally_select(cond, true_var, false_var)?;
-//             selected_vars.push(selected_var);
-//         }
-//
-//         Ok(VecFPVar(selected_vars))
-//     }
-// }
-//
-//
+
+/// Circuit counterpart of [`MonolithDigest`]: `N` field variables.
 #[derive(Clone, Debug)]
-pub struct VecFpVar {
-    pub vars: Vec<FpVar<FP64>>,
-}
+pub struct MonolithDigestVar<const N: usize, F: MonolithField = FP64>(pub [FpVar<F>; N]);
 
-impl AllocVar<Vec<FP64>, FP64> for VecFpVar {
-    fn new_variable<T: std::borrow::Borrow<Vec<FP64>>>(
-        cs: impl Into<ark_relations::r1cs::Namespace<FP64>>,
-        f: impl FnOnce() -> Result<T, ark_relations::r1cs::SynthesisError>,
+impl<const N: usize, F: MonolithField> AllocVar<MonolithDigest<N, F>, F>
+    for MonolithDigestVar<N, F>
+{
+    fn new_variable<T: core::borrow::Borrow<MonolithDigest<N, F>>>(
+        cs: impl Into<ark_relations::r1cs::Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
-    ) -> Result<Self, ark_relations::r1cs::SynthesisError> {
+    ) -> Result<Self, SynthesisError> {
         let ns = cs.into();
         let cs = ns.cs();
 
-        let values = f()?.borrow().clone();
-        let mut vars = Vec::new();
-        for value in values {
-            let var = FpVar::new_variable(cs.clone(), || Ok(value), mode)?;
-            vars.push(var);
+        // The value is only needed when assigning, so a missing one is not an error before then.
+        let value = f().map(|digest| *digest.borrow());
+        let mut vars = Vec::with_capacity(N);
+        for i in 0..N {
+            vars.push(FpVar::new_variable(
+                cs.clone(),
+                || value.map(|digest| digest.0[i]),
+                mode,
+            )?);
         }
-        Ok(VecFpVar { vars })
+        Ok(Self::from_vars(vars))
     }
 }
 
-impl EqGadget<FP64> for VecFpVar {
-    fn is_eq(&self, other: &Self) -> Result<Boolean<FP64>, ark_relations::r1cs::SynthesisError> {
-        assert_eq!(self.vars.len(), other.vars.len());
-        let mut result = Boolean::constant(true);
-        for (a, b) in self.vars.iter().zip(other.vars.iter()) {
-            let eq = a.is_eq(b)?;
-            result = result & (&eq);
-        }
-        Ok(result)
+impl<const N: usize, F: MonolithField> MonolithDigestVar<N, F> {
+    // `vars` holds exactly `N` variables everywhere this is called.
+    fn from_vars(vars: Vec<FpVar<F>>) -> Self {
+        Self(core::array::from_fn(|i| vars[i].clone()))
+    }
+}
+
+impl<const N: usize, F: MonolithField> EqGadget<F> for MonolithDigestVar<N, F> {
+    fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        self.0[..].is_eq(&other.0[..])
     }
 }
 
-impl CondSelectGadget<FP64> for VecFpVar {
+impl<const N: usize, F: MonolithField> CondSelectGadget<F> for MonolithDigestVar<N, F> {
     fn conditionally_select(
-        cond: &Boolean<FP64>,
+        cond: &Boolean<F>,
         true_value: &Self,
         false_value: &Self,
-    ) -> Result<Self, ark_relations::r1cs::SynthesisError> {
-        assert_eq!(true_value.vars.len(), false_value.vars.len());
-        let mut selected_vars = Vec::new();
-        for (t, f) in true_value.vars.iter().zip(false_value.vars.iter()) {
-            let var = FpVar::conditionally_select(cond, t, f)?;
-            selected_vars.push(var);
+    ) -> Result<Self, SynthesisError> {
+        let mut selected_vars = Vec::with_capacity(N);
+        for (t, f) in true_value.0.iter().zip(false_value.0.iter()) {
+            selected_vars.push(FpVar::conditionally_select(cond, t, f)?);
         }
-        Ok(VecFpVar {
-            vars: selected_vars,
-        })
+        Ok(Self::from_vars(selected_vars))
     }
 }
 
-impl ToBytesGadget<FP64> for VecFpVar {
-    fn to_bytes_le(&self) -> Result<Vec<UInt8<FP64>>, ark_relations::r1cs::SynthesisError> {
-        Vec::<_>::to_bytes_le(&self.vars)
+impl<const N: usize, F: MonolithField> ToBytesGadget<F> for MonolithDigestVar<N, F> {
+    fn to_bytes_le(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        self.0[..].to_bytes_le()
+    }
+}
+
+impl<const N: usize, F: MonolithField> AbsorbGadget<F> for MonolithDigestVar<N, F> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        FpVar::batch_to_sponge_bytes(&self.0)
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        FpVar::batch_to_sponge_field_elements(&self.0)
     }
 }
 
-impl R1CSVar<FP64> for VecFpVar {
-    type Value = Vec<FP64>;
+impl<const N: usize, F: MonolithField> R1CSVar<F> for MonolithDigestVar<N, F> {
+    type Value = MonolithDigest<N, F>;
 
-    fn cs(&self) -> ConstraintSystemRef<FP64> {
+    fn cs(&self) -> ConstraintSystemRef<F> {
         let mut result = ConstraintSystemRef::None;
-        for var in self.vars.iter() {
+        for var in self.0.iter() {
             result = var.cs().or(result);
         }
         result
     }
 
-    fn value(&self) -> Result<Self::Value, ark_relations::r1cs::SynthesisError> {
-        let mut result = Vec::new();
-        for var in self.vars.iter() {
-            result.push(var.value()?);
+    fn value(&self) -> Result<Self::Value, SynthesisError> {
+        let mut result = [F::zero(); N];
+        for (value, var) in result.iter_mut().zip(self.0.iter()) {
+            *value = var.value()?;
         }
-        Ok(result)
+        Ok(MonolithDigest(result))
     }
 }
+/// The Monolith parameters as circuit constants.
+///
+/// The gadgets fold the round constants into their linear combinations, so there is nothing to
+/// allocate, and only [`AllocationMode::Constant`] is accepted. Allocating them in witness or
+/// input mode is a misuse of the API rather than a false statement, but `SynthesisError` has no
+/// variant for it, so it fails with [`SynthesisError::Unsatisfiable`] before touching the
+/// constraint system. [`CRHParametersInputVar`] binds the round constants as public inputs
+/// instead.
+#[derive(Clone)]
+pub struct CRHParametersVar<F: MonolithField = FP64> {
+    pub parameters: MonolithParams<F>,
+}
+/// The Monolith parameters with the round constants allocated as variables, usually public
+/// inputs, so that a verifier binds the instance it checks against. The rest of the parameters
+/// shapes the circuit and stays constant. The permutation takes them through
+/// [`MonolithPermuteVar::permute_with_round_constants`].
 #[derive(Clone)]
-pub struct CRHParametersVar {
-    pub parameters: MonolithParams,
+pub struct CRHParametersInputVar<F: MonolithField = FP64> {
+    pub parameters: MonolithParams<F>,
+    /// One row of variables for each row of `parameters.round_constants`.
+    pub round_constants: Vec<Vec<FpVar<F>>>,
+}
+/// The parameters the Monolith gadgets accept: [`CRHParametersVar`], the default, or
+/// [`CRHParametersInputVar`], selected with the last type parameter of a gadget, as in
+/// `CRHGadget<12, 4, CRHParametersInputVar>`.
+pub trait MonolithParametersVar<F: MonolithField>: AllocVar<MonolithParams<F>, F> + Clone {
+    /// The native parameters. Their round constants are only used when nothing is a variable.
+    fn parameters(&self) -> &MonolithParams<F>;
+    /// The round constants as variables, if they are not constants.
+    fn round_constants(&self) -> Option<&[Vec<FpVar<F>>]>;
+    /// The constraint system of the round constants, if they are variables.
+    fn cs(&self) -> ConstraintSystemRef<F>;
+}
+impl<F: MonolithField> MonolithParametersVar<F> for CRHParametersVar<F> {
+    fn parameters(&self) -> &MonolithParams<F> {
+        &self.parameters
+    }
+    fn round_constants(&self) -> Option<&[Vec<FpVar<F>>]> {
+        None
+    }
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        ConstraintSystemRef::None
+    }
+}
+impl<F: MonolithField> MonolithParametersVar<F> for CRHParametersInputVar<F> {
+    fn parameters(&self) -> &MonolithParams<F> {
+        &self.parameters
+    }
+    fn round_constants(&self) -> Option<&[Vec<FpVar<F>>]> {
+        Some(&self.round_constants)
+    }
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.round_constants
+            .iter()
+            .flatten()
+            .fold(ConstraintSystemRef::None, |cs, rc| cs.or(rc.cs()))
+    }
+}
+pub struct CRHGadget<const T: usize, const N: usize = 4, P = CRHParametersVar> {
+    field_phantom: PhantomData<(FP64, P)>,
+}
+/// Gadget for [`FixedLengthCRH64`].
+pub struct FixedLengthCRHGadget<const T: usize, const N: usize = 4, P = CRHParametersVar> {
+    field_phantom: PhantomData<(FP64, P)>,
 }
-pub struct CRHGadget<const T: usize> {
-    field_phantom: PhantomData<FP64>,
+/// Gadget for [`MonolithBytes`], packing the input bytes with linear combinations only.
+pub struct MonolithBytesGadget<const N: usize = 4, P = CRHParametersVar> {
+    field_phantom: PhantomData<(FP64, P)>,
 }
 
-impl AllocVar<MonolithParams, FP64> for CRHParametersVar {
-    fn new_variable<T: core::borrow::Borrow<MonolithParams>>(
-        _cs: impl Into<ark_relations::r1cs::Namespace<FP64>>,
+/// Circuit counterpart of the native Monolith sponge hash: the same domain tag in the first
+/// capacity element and, in variable-length mode, the same one-and-zeros padding, or the input
+/// length in the second capacity element in fixed-length mode. Parameters for another width than
+/// `T` make the hash unsatisfiable, as they make the native hash fail.
+fn sponge_hash_var<F: MonolithField, const T: usize, const N: usize>(
+    cs: ConstraintSystemRef<F>,
+    parameters: &impl MonolithParametersVar<F>,
+    capacity: usize,
+    input: &[FpVar<F>],
+    fixed_length: bool,
+) -> Result<MonolithDigestVar<N, F>, SynthesisError> {
+    let rate = T
+        .checked_sub(capacity)
+        .ok_or(SynthesisError::Unsatisfiable)?;
+    let sponge_config = SpongeConfig::<F, T>::new(rate, capacity, parameters.parameters())
+        .map_err(|_| SynthesisError::Unsatisfiable)?;
+    let mut sponge = MonolithSpongeVar::<F, T>::new(cs, &sponge_config);
+    sponge.round_constants = parameters.round_constants().map(<[_]>::to_vec);
+    if fixed_length {
+        sponge.state[0] = FpVar::Constant(F::from(FIXED_LENGTH_DOMAIN));
+        sponge.state[1] = FpVar::Constant(F::from(input.len() as u64));
+        sponge.absorb(&input)?;
+    } else {
+        sponge.state[0] = FpVar::Constant(F::from(VARIABLE_LENGTH_DOMAIN));
+        let mut padded = input.to_vec();
+        padded.push(FpVar::Constant(F::one()));
+        padded.resize(
+            padded.len().div_ceil(rate) * rate,
+            FpVar::Constant(F::zero()),
+        );
+        sponge.absorb(&padded)?;
+    }
+    Ok(MonolithDigestVar::from_vars(
+        sponge.squeeze_field_elements(N)?,
+    ))
+}
+
+/// The permutation with the round constants of `parameters`, constants or variables.
+fn permute_var<F: MonolithField, const T: usize>(
+    state: &mut [FpVar<F>],
+    parameters: &impl MonolithParametersVar<F>,
+) -> Result<(), SynthesisError> {
+    let permutation = MonolithPermuteVar::<F, T>::default();
+    match parameters.round_constants() {
+        Some(round_constants) => permutation.permute_with_round_constants(
+            state,
+            parameters.parameters(),
+            round_constants,
+        ),
+        None => permutation.permute(state, parameters.parameters()),
+    }
+}
+
+/// Circuit counterpart of the native Monolith compression with a state of `T` elements:
+/// `left + P(left || right || 0)`, truncated to `N` elements.
+fn compress_var<F: MonolithField, const T: usize, const N: usize>(
+    parameters: &impl MonolithParametersVar<F>,
+    left: &MonolithDigestVar<N, F>,
+    right: &MonolithDigestVar<N, F>,
+) -> Result<MonolithDigestVar<N, F>, SynthesisError> {
+    let mut state: Vec<FpVar<F>> = left.0.iter().chain(&right.0).cloned().collect();
+    state.resize(T, FpVar::Constant(F::zero()));
+    permute_var::<F, T>(&mut state, parameters)?;
+    Ok(MonolithDigestVar(core::array::from_fn(|i| {
+        &left.0[i] + &state[i]
+    })))
+}
+
+/// Circuit counterpart of the native Jive compression of `B` digests with a state of `T`
+/// elements.
+fn jive_var<F: MonolithField, const T: usize, const B: usize, const N: usize>(
+    parameters: &impl MonolithParametersVar<F>,
+    inputs: [&MonolithDigestVar<N, F>; B],
+) -> Result<MonolithDigestVar<N, F>, SynthesisError> {
+    let mut state: Vec<FpVar<F>> = inputs.iter().flat_map(|input| input.0.clone()).collect();
+    state.resize(T, FpVar::Constant(F::zero()));
+    permute_var::<F, T>(&mut state, parameters)?;
+    let mut out: [FpVar<F>; N] = core::array::from_fn(|_| FpVar::Constant(F::zero()));
+    for (j, input) in inputs.iter().enumerate() {
+        for (i, o) in out.iter_mut().enumerate() {
+            *o += &input.0[i] + &state[j * N + i];
+        }
+    }
+    Ok(MonolithDigestVar(out))
+}
+
+// The parameters are circuit constants in any constraint field, including the emulated gadgets'.
+impl<F: MonolithField, CF: PrimeField> AllocVar<MonolithParams<F>, CF> for CRHParametersVar<F> {
+    fn new_variable<T: core::borrow::Borrow<MonolithParams<F>>>(
+        _cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
         f: impl FnOnce() -> Result<T, ark_relations::r1cs::SynthesisError>,
-        _mode: AllocationMode,
+        mode: AllocationMode,
     ) -> Result<Self, ark_relations::r1cs::SynthesisError> {
-        f().and_then(|param| {
-            let params = param.borrow().clone();
-            Ok(Self { parameters: params })
+        if mode != AllocationMode::Constant {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let parameters = f()?.borrow().clone();
+        Ok(Self { parameters })
+    }
+}
+impl<F: MonolithField> AllocVar<MonolithParams<F>, F> for CRHParametersInputVar<F> {
+    fn new_variable<T: core::borrow::Borrow<MonolithParams<F>>>(
+        cs: impl Into<ark_relations::r1cs::Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        // The parameters shape the circuit, so unlike other values they are needed in every mode.
+        let parameters = f()?.borrow().clone();
+        let round_constants = parameters
+            .round_constants
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|rc| FpVar::new_variable(cs.clone(), || Ok(*rc), mode))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            parameters,
+            round_constants,
         })
     }
 }
-impl<const T: usize> CRHSchemeGadget<CRH64<T>, FP64> for CRHGadget<T> {
+impl<const T: usize, const N: usize, P: MonolithParametersVar<FP64>>
+    CRHSchemeGadget<CRH64<T, N>, FP64> for CRHGadget<T, N, P>
+{
     type InputVar = [FpVar<FP64>];
-    type OutputVar = VecFpVar;
-    type ParametersVar = CRHParametersVar;
+    type OutputVar = MonolithDigestVar<N>;
+    type ParametersVar = P;
 
     fn evaluate(
         parameters: &Self::ParametersVar,
         input: &Self::InputVar,
     ) -> Result<Self::OutputVar, ark_relations::r1cs::SynthesisError> {
-        let cs = input.cs();
+        let cs = input.cs().or(parameters.cs());
 
         if cs.is_none() {
-            let mut constant_input = Vec::new();
-            for var in input.iter() {
-                constant_input.push(var.value().unwrap());
-            }
-            let hsh: Vec<FP64> =
-                CRH64::<12>::evaluate(&parameters.parameters, constant_input).unwrap();
-            let mut c_hsh: Vec<FpVar<FP64>> = vec![];
-            for val in hsh.iter() {
-                c_hsh.push(FpVar::<_>::new_constant(cs.clone(), val)?);
-            }
-            Ok(VecFpVar { vars: c_hsh })
+            let hsh = CRH64::<T, N>::evaluate(parameters.parameters(), input.value()?)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            MonolithDigestVar::new_constant(cs, hsh)
+        } else {
+            sponge_hash_var::<FP64, T, N>(cs, parameters, 4, input, false)
+        }
+    }
+}
+impl<const T: usize, const N: usize, P: MonolithParametersVar<FP64>>
+    CRHSchemeGadget<FixedLengthCRH64<T, N>, FP64> for FixedLengthCRHGadget<T, N, P>
+{
+    type InputVar = [FpVar<FP64>];
+    type OutputVar = MonolithDigestVar<N>;
+    type ParametersVar = P;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = input.cs().or(parameters.cs());
+
+        if cs.is_none() {
+            let constant_input = input.value()?;
+            let hsh = FixedLengthCRH64::<T, N>::evaluate(parameters.parameters(), constant_input)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            MonolithDigestVar::new_constant(cs, hsh)
         } else {
-            let sponge_config = SpongeConfig::new(8, 4, &parameters.parameters);
-            let mut sponge = MonolithSpongeVar::new(cs, &sponge_config);
+            sponge_hash_var::<FP64, T, N>(cs, parameters, 4, input, true)
+        }
+    }
+}
+impl<const N: usize, P: MonolithParametersVar<FP64>> CRHSchemeGadget<MonolithBytes<N>, FP64>
+    for MonolithBytesGadget<N, P>
+{
+    type InputVar = [UInt8<FP64>];
+    type OutputVar = MonolithDigestVar<N>;
+    type ParametersVar = P;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = input.cs().or(parameters.cs());
 
-            sponge.absorb(&input).unwrap();
-            let res = sponge.squeeze_field_elements(4).unwrap();
-            Ok(VecFpVar { vars: res })
+        if cs.is_none() {
+            let hsh = MonolithBytes::<N>::evaluate(parameters.parameters(), input.value()?)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            MonolithDigestVar::new_constant(cs, hsh)
+        } else {
+            // 56 bits are below the modulus, so no range check is needed.
+            let mut elements = input
+                .chunks(BYTES_PER_ELEMENT)
+                .map(|chunk| Boolean::le_bits_to_fp(&chunk.to_bits_le()?))
+                .collect::<Result<Vec<_>, _>>()?;
+            elements.push(FpVar::Constant(FP64::from(input.len() as u64)));
+            sponge_hash_var::<FP64, 12, N>(cs, parameters, 4, &elements, false)
         }
     }
 }
-pub struct TwoToOneCRHGadget {
-    field_phantom: PhantomData<FP64>,
+/// Gadget for [`TwoToOneCrhScheme64`] on digests of `N` elements.
+pub struct TwoToOneCRHGadget<const N: usize = 4, P = CRHParametersVar> {
+    field_phantom: PhantomData<(FP64, P)>,
 }
-impl TwoToOneCRHSchemeGadget<TwoToOneCrhScheme64, FP64> for TwoToOneCRHGadget {
-    type InputVar = VecFpVar;
-    type OutputVar = VecFpVar;
-    type ParametersVar = CRHParametersVar;
+impl<const N: usize, P: MonolithParametersVar<FP64>>
+    TwoToOneCRHSchemeGadget<TwoToOneCrhScheme64<N>, FP64> for TwoToOneCRHGadget<N, P>
+{
+    type InputVar = MonolithDigestVar<N>;
+    type OutputVar = MonolithDigestVar<N>;
+    type ParametersVar = P;
     fn evaluate(
         parameters: &Self::ParametersVar,
         left_input: &Self::InputVar,
@@ -216,45 +416,62 @@ impl TwoToOneCRHSchemeGadget<TwoToOneCrhScheme64, FP64> for TwoToOneCRHGadget {
         left_input: &Self::OutputVar,
         right_input: &Self::OutputVar,
     ) -> Result<Self::OutputVar, ark_relations::r1cs::SynthesisError> {
-        let cs = left_input.cs().or(right_input.cs());
+        let cs = left_input.cs().or(right_input.cs()).or(parameters.cs());
         if cs.is_none() {
-            let outp = TwoToOneCrhScheme64::evaluate(
-                &parameters.parameters,
+            let outp = TwoToOneCrhScheme64::<N>::compress(
+                parameters.parameters(),
                 left_input.value()?,
                 right_input.value()?,
             )
-            .unwrap();
-
-            Ok(VecFpVar {
-                vars: vec![
-                    FpVar::Constant(outp[0]),
-                    FpVar::Constant(outp[1]),
-                    FpVar::Constant(outp[2]),
-                    FpVar::Constant(outp[3]),
-                ],
-            })
+            .map_err(|_| SynthesisError::Unsatisfiable)?;
+            MonolithDigestVar::new_constant(cs, outp)
+        } else if 2 * N <= 8 {
+            compress_var::<FP64, 8, N>(parameters, left_input, right_input)
         } else {
-            let outp = VecFpVar {
-                vars: left_input.vars.clone(),
-            };
-            let mut inp: Vec<FpVar<FP64>> = vec![];
-            for val in left_input.vars.iter() {
-                inp.push(val.clone());
-            }
-            for val in right_input.vars.iter() {
-                inp.push(val.clone());
-            }
-            let permute_var = MonolithPermuteVar::<8>;
-            permute_var.permute(inp.as_mut_slice(), &parameters.parameters)?;
-            let outp = VecFpVar {
-                vars: outp
-                    .vars
-                    .iter()
-                    .zip(inp.clone())
-                    .map(|(x, y)| x + y)
-                    .collect(),
-            };
-            Ok(outp)
+            compress_var::<FP64, 12, N>(parameters, left_input, right_input)
+        }
+    }
+}
+/// Gadget for [`ThreeToOneCrhScheme64`] on digests of `N` elements.
+pub struct ThreeToOneCRHGadget<const N: usize = 4, P = CRHParametersVar> {
+    field_phantom: PhantomData<(FP64, P)>,
+}
+impl<const N: usize, P: MonolithParametersVar<FP64>>
+    ThreeToOneCRHSchemeGadget<ThreeToOneCrhScheme64<N>, FP64> for ThreeToOneCRHGadget<N, P>
+{
+    type InputVar = MonolithDigestVar<N>;
+    type OutputVar = MonolithDigestVar<N>;
+    type ParametersVar = P;
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        center_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        Self::compress(parameters, left_input, center_input, right_input)
+    }
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        center_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = left_input
+            .cs()
+            .or(center_input.cs())
+            .or(right_input.cs())
+            .or(parameters.cs());
+        if cs.is_none() {
+            let outp = ThreeToOneCrhScheme64::<N>::compress(
+                parameters.parameters(),
+                left_input.value()?,
+                center_input.value()?,
+                right_input.value()?,
+            )
+            .map_err(|_| SynthesisError::Unsatisfiable)?;
+            MonolithDigestVar::new_constant(cs, outp)
+        } else {
+            jive_var::<FP64, 12, 3, N>(parameters, [left_input, center_input, right_input])
         }
     }
 }
@@ -263,114 +480,548 @@ mod test {
     use ark_ff::UniformRand;
     use ark_r1cs_std::alloc::AllocVar;
     use ark_r1cs_std::fields::fp::FpVar;
-    use ark_r1cs_std::fields::FieldVar;
+    use ark_r1cs_std::uint8::UInt8;
     use ark_r1cs_std::R1CSVar;
-    use ark_relations::r1cs::ConstraintSystem;
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
     use ark_std::iterable::Iterable;
+    use ark_std::rand::Rng;
 
     use crate::crh::monolith::constraints::CRHGadget;
     use crate::crh::monolith::constraints::CRHParametersVar;
+    use crate::crh::monolith::constraints::FixedLengthCRHGadget;
+    use crate::crh::monolith::constraints::MonolithBytesGadget;
+    use crate::crh::monolith::constraints::ThreeToOneCRHGadget;
     use crate::crh::monolith::constraints::TwoToOneCRHGadget;
     use crate::crh::monolith::constraints::TwoToOneCrhScheme64;
     use crate::crh::monolith::fields::goldilocks::Fr as FP64;
-    use crate::crh::monolith::CRH64;
+    use crate::crh::monolith::{FixedLengthCRH64, MonolithBytes, ThreeToOneCrhScheme64, CRH64};
     use crate::crh::TwoToOneCRHScheme;
     use crate::crh::TwoToOneCRHSchemeGadget;
     use crate::crh::{CRHScheme, CRHSchemeGadget};
+    use crate::crh::{ThreeToOneCRHScheme, ThreeToOneCRHSchemeGadget};
 
-    use super::VecFpVar;
+    use crate::crh::monolith::MonolithDigest;
+
+    use super::MonolithDigestVar;
+    use crate::sponge::constraints::AbsorbGadget;
+    use ark_r1cs_std::boolean::Boolean;
+    use ark_r1cs_std::eq::EqGadget;
+    use ark_r1cs_std::select::CondSelectGadget;
     #[test]
     fn test_consistency() {
         let mut test_rng = ark_std::test_rng();
         let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
-        let inp = [
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-            // FP64::rand(&mut test_rng),
-        ];
+        let inp: [FP64; 3] = core::array::from_fn(|_| FP64::rand(&mut test_rng));
         let cs = ConstraintSystem::<FP64>::new_ref();
-        let mut inp_var = [
-            FpVar::<FP64>::zero(),
-            FpVar::<FP64>::zero(),
-            FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-            // FpVar::<FP64>::zero(),
-        ];
-        for i in 0..inp.len() {
-            inp_var[i] =
-                FpVar::new_witness(cs.clone(), || Ok(inp[i].clone())).expect("inp var failed");
-        }
-        println!("inp : {:?}", inp);
-        println!("inp_var: {:?}", inp_var.value().unwrap());
+        let inp_var = inp.map(|x| FpVar::new_witness(cs.clone(), || Ok(x)).unwrap());
         let params_var =
-            CRHParametersVar::new_witness(cs.clone(), || Ok(monolith_params.clone())).unwrap();
+            CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
         let outp_var = CRHGadget::<12>::evaluate(&params_var, &inp_var).unwrap();
         let outp = CRH64::<12>::evaluate(&monolith_params, inp).unwrap();
-        println!("num constraints: {:?}", cs.num_constraints());
-        assert_eq!(outp, outp_var.value().unwrap())
+        assert_eq!(outp, outp_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
     }
+    /// Checks both Monolith-64 sponge gadgets of width `T` against the native hashes, on witness
+    /// and constant inputs of every length up to three rate blocks.
+    fn check_consistency_width<const T: usize>() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<T>::setup(&mut test_rng).unwrap();
+        let rate = T - 4;
+        for len in 0..=3 * rate {
+            let inp: Vec<FP64> = (0..len).map(|_| FP64::rand(&mut test_rng)).collect();
+            let outp = CRH64::<T>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+            let fixed_outp =
+                FixedLengthCRH64::<T>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+
+            let cs = ConstraintSystem::<FP64>::new_ref();
+            let params_var =
+                CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+            let inp_var: Vec<FpVar<FP64>> = inp
+                .iter()
+                .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                .collect();
+            let outp_var = CRHGadget::<T>::evaluate(&params_var, &inp_var).unwrap();
+            assert_eq!(outp, outp_var.value().unwrap());
+            let outp_var = FixedLengthCRHGadget::<T>::evaluate(&params_var, &inp_var).unwrap();
+            assert_eq!(fixed_outp, outp_var.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+
+            let inp_var: Vec<FpVar<FP64>> = inp.iter().map(|x| FpVar::Constant(*x)).collect();
+            let outp_var = CRHGadget::<T>::evaluate(&params_var, &inp_var).unwrap();
+            assert!(outp_var.0.iter().all(|v| v.is_constant()));
+            assert_eq!(outp, outp_var.value().unwrap());
+            let outp_var = FixedLengthCRHGadget::<T>::evaluate(&params_var, &inp_var).unwrap();
+            assert_eq!(fixed_outp, outp_var.value().unwrap());
+        }
+    }
+
     #[test]
-    fn test_consistency_two() {
+    fn test_consistency_widths() {
+        check_consistency_width::<8>();
+        check_consistency_width::<12>();
+
+        // Width-8 and width-12 hashes of the same input differ.
+        let inp = [FP64::from(1u64), FP64::from(2u64)];
         let mut test_rng = ark_std::test_rng();
-        let monolith_params = TwoToOneCrhScheme64::setup(&mut test_rng).unwrap();
-        let inp1 = vec![
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-        ];
-        let inp2 = vec![
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-            FP64::rand(&mut test_rng),
-        ];
-        let mut inp1_var = vec![];
-        let mut inp2_var = vec![];
+        assert_ne!(
+            CRH64::<8>::evaluate(&CRH64::<8>::setup(&mut test_rng).unwrap(), inp.as_slice())
+                .unwrap(),
+            CRH64::<12>::evaluate(&CRH64::<12>::setup(&mut test_rng).unwrap(), inp.as_slice())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrong_width_parameters() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
         let cs = ConstraintSystem::<FP64>::new_ref();
-        // cs.set_optimization_goal(ark_relations::r1cs::OptimizationGoal::Constraints);
-        for val in inp1.iter() {
-            inp1_var.push(
-                FpVar::<FP64>::new_witness(cs.clone(), || Ok(val.clone())).expect("inp var failed"),
-            );
+        let params_var = CRHParametersVar::new_constant(cs.clone(), monolith_params).unwrap();
+        let witness = [FpVar::new_witness(cs.clone(), || Ok(FP64::from(1u64))).unwrap()];
+        let constant = [FpVar::Constant(FP64::from(1u64))];
+        for inp_var in [&witness, &constant] {
+            assert!(CRHGadget::<8>::evaluate(&params_var, inp_var).is_err());
+            assert!(FixedLengthCRHGadget::<8>::evaluate(&params_var, inp_var).is_err());
         }
-        for val in inp2.iter() {
-            inp2_var.push(
-                FpVar::<FP64>::new_witness(cs.clone(), || Ok(val.clone())).expect("inp var failed"),
-            );
+    }
+
+    #[test]
+    fn test_consistency_bytes() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = MonolithBytes::<4>::setup(&mut test_rng).unwrap();
+        for len in [0, 1, 6, 7, 8, 15, 60] {
+            let inp: Vec<u8> = (0..len).map(|_| test_rng.gen()).collect();
+            let outp = MonolithBytes::<4>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+
+            let cs = ConstraintSystem::<FP64>::new_ref();
+            let inp_var = UInt8::new_witness_vec(cs.clone(), &inp).unwrap();
+            let params_var =
+                CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+            let outp_var = MonolithBytesGadget::<4>::evaluate(&params_var, &inp_var).unwrap();
+            assert_eq!(outp, outp_var.value().unwrap());
+            assert!(cs.is_satisfied().unwrap());
+
+            let inp_const = UInt8::constant_vec(&inp);
+            let outp_const = MonolithBytesGadget::<4>::evaluate(&params_var, &inp_const).unwrap();
+            assert_eq!(outp, outp_const.value().unwrap());
         }
-        let params_var = CRHParametersVar::new_witness(cs.clone(), || Ok(monolith_params.clone()))
-            .expect("param var failed");
+    }
+    #[test]
+    fn test_consistency_two() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = TwoToOneCrhScheme64::<4>::setup(&mut test_rng).unwrap();
+        let inp1 = MonolithDigest::<4>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let inp2 = MonolithDigest::<4>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let inp1_var = MonolithDigestVar::new_witness(cs.clone(), || Ok(inp1)).unwrap();
+        let inp2_var = MonolithDigestVar::new_witness(cs.clone(), || Ok(inp2)).unwrap();
+        let params_var =
+            CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
         let outp = TwoToOneCrhScheme64::evaluate(&monolith_params, inp1, inp2).unwrap();
         let outp_var = <TwoToOneCRHGadget as TwoToOneCRHSchemeGadget<_, _>>::compress(
             &params_var,
-            &VecFpVar { vars: inp1_var },
-            &VecFpVar { vars: inp2_var },
+            &inp1_var,
+            &inp2_var,
+        )
+        .unwrap();
+        assert_eq!(outp, outp_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+    #[test]
+    fn test_consistency_digest_lengths() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let inp: Vec<FP64> = (0..5).map(|_| FP64::rand(&mut test_rng)).collect();
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let inp_var: Vec<FpVar<FP64>> = inp
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect();
+        let params_var =
+            CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+        let outp_var = CRHGadget::<12, 2>::evaluate(&params_var, &inp_var).unwrap();
+        let outp = CRH64::<12, 2>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+        assert_eq!(outp, outp_var.value().unwrap());
+        let outp_var = CRHGadget::<12, 6>::evaluate(&params_var, &inp_var).unwrap();
+        let outp = CRH64::<12, 6>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+        assert_eq!(outp, outp_var.value().unwrap());
+
+        let monolith_params = TwoToOneCrhScheme64::<6>::setup(&mut test_rng).unwrap();
+        let inp1 = MonolithDigest::<6>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let inp2 = MonolithDigest::<6>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let params_var =
+            CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+        let inp1_var = MonolithDigestVar::new_witness(cs.clone(), || Ok(inp1)).unwrap();
+        let inp2_var = MonolithDigestVar::new_witness(cs.clone(), || Ok(inp2)).unwrap();
+        let outp_var = TwoToOneCRHGadget::<6>::compress(&params_var, &inp1_var, &inp2_var).unwrap();
+        let outp = TwoToOneCrhScheme64::<6>::compress(&monolith_params, inp1, inp2).unwrap();
+        assert_eq!(outp, outp_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+    #[test]
+    fn test_digest_var() {
+        let mut test_rng = ark_std::test_rng();
+        let a = MonolithDigest::<4>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let b = MonolithDigest::<4>(core::array::from_fn(|_| FP64::rand(&mut test_rng)));
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let a_var = MonolithDigestVar::new_witness(cs.clone(), || Ok(a)).unwrap();
+        let b_var = MonolithDigestVar::new_input(cs.clone(), || Ok(b)).unwrap();
+        assert_eq!(a_var.value().unwrap(), a);
+        assert!(!a_var.is_eq(&b_var).unwrap().value().unwrap());
+
+        let cond = Boolean::new_witness(cs.clone(), || Ok(false)).unwrap();
+        let selected = MonolithDigestVar::conditionally_select(&cond, &a_var, &b_var).unwrap();
+        selected.enforce_equal(&b_var).unwrap();
+        assert_eq!(
+            selected
+                .to_sponge_field_elements()
+                .unwrap()
+                .value()
+                .unwrap(),
+            b.0.to_vec()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+    #[test]
+    fn test_consistency_three() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = ThreeToOneCrhScheme64::<4>::setup(&mut test_rng).unwrap();
+        let inputs: [MonolithDigest<4>; 3] = core::array::from_fn(|_| {
+            MonolithDigest(core::array::from_fn(|_| FP64::rand(&mut test_rng)))
+        });
+        let outp =
+            ThreeToOneCrhScheme64::compress(&monolith_params, inputs[0], inputs[1], inputs[2])
+                .unwrap();
+
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let params_var =
+            CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+        let input_vars =
+            inputs.map(|input| MonolithDigestVar::new_witness(cs.clone(), || Ok(input)).unwrap());
+        let outp_var = ThreeToOneCRHGadget::<4>::compress(
+            &params_var,
+            &input_vars[0],
+            &input_vars[1],
+            &input_vars[2],
+        )
+        .unwrap();
+        assert_eq!(outp, outp_var.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+
+        let constant_vars =
+            inputs.map(|input| MonolithDigestVar::new_constant(cs.clone(), input).unwrap());
+        let outp_const = ThreeToOneCRHGadget::<4>::compress(
+            &params_var,
+            &constant_vars[0],
+            &constant_vars[1],
+            &constant_vars[2],
+        )
+        .unwrap();
+        assert_eq!(outp, outp_const.value().unwrap());
+    }
+    #[test]
+    fn test_consistency_quadratic_extension_sponge() {
+        use crate::crh::monolith::fields::goldilocks::Fr2;
+        use crate::sponge::constraints::CryptographicSpongeVar;
+        use crate::sponge::generic::constraints::MonolithSpongeVar;
+        use crate::sponge::generic::generic_sponge::{MonolithSponge, SpongeConfig};
+        use crate::sponge::CryptographicSponge;
+        use ark_r1cs_std::fields::fp2::Fp2Var;
+
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let sponge_config = SpongeConfig::new(8, 4, &monolith_params).unwrap();
+        let inputs: Vec<Fr2> = (0..5).map(|_| Fr2::rand(&mut test_rng)).collect();
+
+        let mut sponge = MonolithSponge::<FP64, 12>::new(&sponge_config);
+        sponge.absorb(&inputs);
+        let challenges = sponge.squeeze_quadratic_extension_elements(3);
+
+        // Absorbing an extension element is absorbing its two coefficients.
+        let mut flat = MonolithSponge::<FP64, 12>::new(&sponge_config);
+        let coefficients: Vec<FP64> = inputs.iter().flat_map(|x| [x.c0, x.c1]).collect();
+        flat.absorb(&coefficients);
+        assert_eq!(challenges, flat.squeeze_quadratic_extension_elements(3));
+
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let input_vars: Vec<Fp2Var<_>> = inputs
+            .iter()
+            .map(|x| Fp2Var::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect();
+        let mut sponge_var = MonolithSpongeVar::<FP64, 12>::new(cs.clone(), &sponge_config);
+        sponge_var.absorb(&input_vars).unwrap();
+        let challenge_vars = sponge_var.squeeze_quadratic_extension_elements(3).unwrap();
+        let challenge_values: Vec<Fr2> =
+            challenge_vars.iter().map(|c| c.value().unwrap()).collect();
+        assert_eq!(challenges, challenge_values);
+        assert!(cs.is_satisfied().unwrap());
+    }
+    #[test]
+    fn test_parameters_are_constants() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        assert!(matches!(
+            CRHParametersVar::new_witness(cs.clone(), || Ok(monolith_params.clone())),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        assert!(matches!(
+            CRHParametersVar::new_input(cs.clone(), || Ok(monolith_params.clone())),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        CRHParametersVar::new_constant(cs.clone(), monolith_params).unwrap();
+        assert_eq!(cs.num_instance_variables(), 1);
+        assert_eq!(cs.num_witness_variables(), 0);
+    }
+    #[test]
+    fn test_parameters_input() {
+        use crate::crh::monolith::constraints::CRHParametersInputVar;
+        use crate::crh::monolith::permute::constraints::MonolithPermuteVar;
+        use crate::crh::monolith::permute::MonolithPermute;
+
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let state: [FP64; 12] = core::array::from_fn(|_| FP64::rand(&mut test_rng));
+        let mut expected = state;
+        MonolithPermute::<FP64, 12>::permute(&mut expected, &monolith_params).unwrap();
+
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let params_var =
+            CRHParametersInputVar::new_input(cs.clone(), || Ok(monolith_params.clone())).unwrap();
+        let rounds = monolith_params.round_constants.len();
+        assert_eq!(cs.num_instance_variables(), 1 + rounds * 12);
+
+        let mut state_var: Vec<FpVar<FP64>> = state
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(x)).unwrap())
+            .collect();
+        let permute = MonolithPermuteVar::<FP64, 12>::default();
+        permute
+            .permute_with_round_constants(
+                &mut state_var,
+                &params_var.parameters,
+                &params_var.round_constants,
+            )
+            .unwrap();
+        assert_eq!(state_var.value().unwrap(), expected);
+        assert!(cs.is_satisfied().unwrap());
+
+        // Different public round constants give a different permutation.
+        let mut other_params = monolith_params.clone();
+        other_params.round_constants[0][0] += FP64::from(1u64);
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let params_var = CRHParametersInputVar::new_input(cs.clone(), || Ok(other_params)).unwrap();
+        let mut state_var: Vec<FpVar<FP64>> = state
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(x)).unwrap())
+            .collect();
+        permute
+            .permute_with_round_constants(
+                &mut state_var,
+                &params_var.parameters,
+                &params_var.round_constants,
+            )
+            .unwrap();
+        assert_ne!(state_var.value().unwrap(), expected);
+
+        // A row of round constants missing is an error.
+        assert_eq!(
+            permute.permute_with_round_constants(
+                &mut state_var,
+                &params_var.parameters,
+                &params_var.round_constants[1..],
+            ),
+            Err(SynthesisError::Unsatisfiable)
+        );
+    }
+
+    #[test]
+    fn test_parameters_input_gadgets() {
+        use crate::crh::monolith::constraints::CRHParametersInputVar;
+
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let inputs: Vec<FP64> = (0..10).map(|_| FP64::rand(&mut test_rng)).collect();
+        let digests: [MonolithDigest<4>; 3] =
+            core::array::from_fn(|_| MonolithDigest(test_rng.gen()));
+        let rounds = monolith_params.round_constants.len();
+
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let params_var =
+            CRHParametersInputVar::new_input(cs.clone(), || Ok(monolith_params.clone())).unwrap();
+        let inputs_var: Vec<FpVar<FP64>> = inputs
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(x)).unwrap())
+            .collect();
+        let digests_var: Vec<MonolithDigestVar<4>> = (0..3)
+            .map(|i| MonolithDigestVar::new_witness(cs.clone(), || Ok(digests[i])).unwrap())
+            .collect();
+
+        let hash = <CRHGadget<12, 4, CRHParametersInputVar> as CRHSchemeGadget<_, _>>::evaluate(
+            &params_var,
+            &inputs_var,
         )
         .unwrap();
-        cs.finalize();
-        // println!("A: {:?}", cs.to_matrices().unwrap().a);
-        // println!("B: {:?}", cs.to_matrices().unwrap().b);
-        // println!("C: {:?}", cs.to_matrices().unwrap().c);
-        println!("num cons: {:?}", cs.num_constraints());
-        println!("outp: {:?}", outp);
-        println!("outp_var: {:?}", outp_var.vars.value().unwrap());
-        assert_eq!(outp, outp_var.vars.value().unwrap());
+        assert_eq!(
+            hash.value().unwrap(),
+            CRH64::<12>::evaluate(&monolith_params, inputs.as_slice()).unwrap()
+        );
+        let fixed = <FixedLengthCRHGadget<12, 4, CRHParametersInputVar> as CRHSchemeGadget<
+            _,
+            _,
+        >>::evaluate(&params_var, &inputs_var)
+        .unwrap();
+        assert_eq!(
+            fixed.value().unwrap(),
+            FixedLengthCRH64::<12>::evaluate(&monolith_params, inputs.as_slice()).unwrap()
+        );
+        let three = <ThreeToOneCRHGadget<4, CRHParametersInputVar> as ThreeToOneCRHSchemeGadget<
+            _,
+            _,
+        >>::compress(
+            &params_var,
+            &digests_var[0],
+            &digests_var[1],
+            &digests_var[2],
+        )
+        .unwrap();
+        assert_eq!(
+            three.value().unwrap(),
+            ThreeToOneCrhScheme64::<4>::compress(
+                &monolith_params,
+                digests[0],
+                digests[1],
+                digests[2]
+            )
+            .unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.num_instance_variables(), 1 + rounds * 12);
+
+        // The width-8 compression takes its own parameters.
+        let two_params = TwoToOneCrhScheme64::<4>::setup(&mut test_rng).unwrap();
+        let two_params_var =
+            CRHParametersInputVar::new_input(cs.clone(), || Ok(two_params.clone())).unwrap();
+        let two = <TwoToOneCRHGadget<4, CRHParametersInputVar> as TwoToOneCRHSchemeGadget<
+            _,
+            _,
+        >>::compress(&two_params_var, &digests_var[0], &digests_var[1])
+        .unwrap();
+        assert_eq!(
+            two.value().unwrap(),
+            TwoToOneCrhScheme64::<4>::compress(&two_params, digests[0], digests[1]).unwrap()
+        );
+
+        // With constant inputs the round constants still come from the public inputs, so the
+        // digest is a variable bound to them.
+        let constant_inputs: Vec<FpVar<FP64>> =
+            inputs.iter().map(|x| FpVar::Constant(*x)).collect();
+        let hash = <CRHGadget<12, 4, CRHParametersInputVar> as CRHSchemeGadget<_, _>>::evaluate(
+            &params_var,
+            &constant_inputs,
+        )
+        .unwrap();
+        assert!(!hash.0[0].is_constant());
+        assert_eq!(
+            hash.value().unwrap(),
+            CRH64::<12>::evaluate(&monolith_params, inputs.as_slice()).unwrap()
+        );
+        assert!(cs.is_satisfied().unwrap());
+
+        // Other public round constants give another digest.
+        let mut other_params = monolith_params.clone();
+        other_params.round_constants[1][3] += FP64::from(1u64);
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let params_var = CRHParametersInputVar::new_input(cs.clone(), || Ok(other_params)).unwrap();
+        let inputs_var: Vec<FpVar<FP64>> = inputs
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(x)).unwrap())
+            .collect();
+        let hash = <CRHGadget<12, 4, CRHParametersInputVar> as CRHSchemeGadget<_, _>>::evaluate(
+            &params_var,
+            &inputs_var,
+        )
+        .unwrap();
+        assert_ne!(
+            hash.value().unwrap(),
+            CRH64::<12>::evaluate(&monolith_params, inputs.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_setup_mode() {
+        use crate::crh::monolith::constraints::CRHParametersInputVar;
+        use crate::crh::monolith::permute::constraints::MonolithPermuteVar;
+        use crate::sponge::constraints::{check_setup_mode, CryptographicSpongeVar};
+        use crate::sponge::generic::constraints::MonolithSpongeVar;
+        use crate::sponge::generic::generic_sponge::SpongeConfig;
+        use ark_r1cs_std::prelude::{ToBitsGadget, ToBytesGadget};
+
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let params_var = CRHParametersVar::new_constant(
+            ConstraintSystemRef::<FP64>::None,
+            monolith_params.clone(),
+        )
+        .unwrap();
+        let inputs: Vec<FP64> = (0..12).map(|_| FP64::rand(&mut test_rng)).collect();
+        let digest = |vars: &[FpVar<FP64>]| {
+            MonolithDigestVar::<4>(core::array::from_fn(|i| vars[i].clone()))
+        };
+        let output = |digest: MonolithDigestVar<4>| digest.0.to_vec();
+
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            CRHGadget::<12>::evaluate(&params_var, vars).map(output)
+        });
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            FixedLengthCRHGadget::<12>::evaluate(&params_var, vars).map(output)
+        });
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            let bytes = vars.to_bytes_le()?;
+            MonolithBytesGadget::<4>::evaluate(&params_var, &bytes).map(output)
+        });
+        // Two digests of 4 elements are compressed with a state of 8.
+        let two_to_one_params = CRHParametersVar::new_constant(
+            ConstraintSystemRef::<FP64>::None,
+            TwoToOneCrhScheme64::<4>::setup(&mut test_rng).unwrap(),
+        )
+        .unwrap();
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            TwoToOneCRHGadget::<4>::compress(&two_to_one_params, &digest(vars), &digest(&vars[4..]))
+                .map(output)
+        });
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            ThreeToOneCRHGadget::<4>::compress(
+                &params_var,
+                &digest(vars),
+                &digest(&vars[4..]),
+                &digest(&vars[8..]),
+            )
+            .map(output)
+        });
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            let cs = vars.cs();
+            let params_var = CRHParametersInputVar::new_input(cs, || Ok(monolith_params.clone()))?;
+            let mut state = vars.to_vec();
+            MonolithPermuteVar::<FP64, 12>::default().permute_with_round_constants(
+                &mut state,
+                &params_var.parameters,
+                &params_var.round_constants,
+            )?;
+            Ok(state)
+        });
+
+        let sponge_config = SpongeConfig::new(8, 4, &monolith_params).unwrap();
+        check_setup_mode(&inputs, |vars: &[FpVar<FP64>]| {
+            let mut sponge = MonolithSpongeVar::<FP64, 12>::new(vars.cs(), &sponge_config);
+            sponge.absorb(&vars)?;
+            let mut out = sponge.squeeze_field_elements(10)?;
+            sponge.absorb(&vars[0])?;
+            out.extend(sponge.squeeze_bits(70)?.iter().cloned().map(FpVar::from));
+            for byte in sponge.squeeze_bytes(9)? {
+                out.push(Boolean::le_bits_to_fp(&byte.to_bits_le()?)?);
+            }
+            Ok(out)
+        });
     }
 }
@@ -0,0 +1,244 @@
+//! Gadgets for the Monolith-64 hashes in a constraint system over another field than
+//! Goldilocks, built on [`EmulatedMonolithPermuteVar`].
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::prelude::{ToBytesGadget, UInt8};
+use ark_r1cs_std::select::CondSelectGadget;
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::crh::monolith::constraints::CRHParametersVar;
+use crate::crh::monolith::fields::goldilocks::Fr as F64;
+use crate::crh::monolith::permute::emulated::{EmulatedMonolithPermuteVar, F64Var};
+use crate::crh::monolith::{MonolithDigest, MonolithParams, CRH64, VARIABLE_LENGTH_DOMAIN};
+use crate::crh::{CRHScheme, CRHSchemeGadget};
+
+/// Circuit counterpart of a Monolith-64 [`MonolithDigest`] in a constraint system over `CF`: `N`
+/// emulated Goldilocks variables.
+#[derive(Clone, Debug)]
+pub struct EmulatedMonolithDigestVar<const N: usize, CF: PrimeField>(pub [F64Var<CF>; N]);
+
+impl<const N: usize, CF: PrimeField> EmulatedMonolithDigestVar<N, CF> {
+    // `vars` holds exactly `N` variables everywhere this is called.
+    fn from_vars(vars: Vec<F64Var<CF>>) -> Self {
+        Self(core::array::from_fn(|i| vars[i].clone()))
+    }
+}
+
+impl<const N: usize, CF: PrimeField> AllocVar<MonolithDigest<N>, CF>
+    for EmulatedMonolithDigestVar<N, CF>
+{
+    fn new_variable<T: core::borrow::Borrow<MonolithDigest<N>>>(
+        cs: impl Into<ark_relations::r1cs::Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+
+        // The value is only needed when assigning, so a missing one is not an error before then.
+        let value = f().map(|digest| *digest.borrow());
+        let mut vars = Vec::with_capacity(N);
+        for i in 0..N {
+            vars.push(F64Var::new_variable(
+                cs.clone(),
+                || value.map(|digest| digest.0[i]),
+                mode,
+            )?);
+        }
+        Ok(Self::from_vars(vars))
+    }
+}
+
+impl<const N: usize, CF: PrimeField> EqGadget<CF> for EmulatedMonolithDigestVar<N, CF> {
+    fn is_eq(&self, other: &Self) -> Result<Boolean<CF>, SynthesisError> {
+        self.0[..].is_eq(&other.0[..])
+    }
+}
+
+impl<const N: usize, CF: PrimeField> CondSelectGadget<CF> for EmulatedMonolithDigestVar<N, CF> {
+    fn conditionally_select(
+        cond: &Boolean<CF>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        let mut selected_vars = Vec::with_capacity(N);
+        for (t, f) in true_value.0.iter().zip(false_value.0.iter()) {
+            selected_vars.push(F64Var::conditionally_select(cond, t, f)?);
+        }
+        Ok(Self::from_vars(selected_vars))
+    }
+}
+
+impl<const N: usize, CF: PrimeField> ToBytesGadget<CF> for EmulatedMonolithDigestVar<N, CF> {
+    fn to_bytes_le(&self) -> Result<Vec<UInt8<CF>>, SynthesisError> {
+        self.0[..].to_bytes_le()
+    }
+}
+
+impl<const N: usize, CF: PrimeField> R1CSVar<CF> for EmulatedMonolithDigestVar<N, CF> {
+    type Value = MonolithDigest<N>;
+
+    fn cs(&self) -> ConstraintSystemRef<CF> {
+        self.0[..].cs()
+    }
+
+    fn value(&self) -> Result<Self::Value, SynthesisError> {
+        let mut result = [F64::from(0u64); N];
+        for (value, var) in result.iter_mut().zip(self.0.iter()) {
+            *value = var.value()?;
+        }
+        Ok(MonolithDigest(result))
+    }
+}
+
+/// Gadget for [`CRH64`] with a state of `T` elements in a constraint system over `CF`.
+pub struct EmulatedCRHGadget<CF: PrimeField, const T: usize, const N: usize = 4> {
+    field_phantom: PhantomData<CF>,
+}
+
+/// Circuit counterpart of the variable-length Monolith-64 sponge hash of [`CRH64`], with a
+/// capacity of 4 elements. Parameters for another width than `T` fail with
+/// [`SynthesisError::Unsatisfiable`] before any constraint is added.
+fn sponge_hash_emulated<CF: PrimeField, const T: usize, const N: usize>(
+    parameters: &MonolithParams<F64>,
+    input: &[F64Var<CF>],
+) -> Result<EmulatedMonolithDigestVar<N, CF>, SynthesisError> {
+    if parameters.state_size() != T {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let capacity = 4;
+    let rate = T - capacity;
+    let permute = EmulatedMonolithPermuteVar::<CF, T>::default();
+
+    let mut state = vec![F64Var::zero(); T];
+    state[0] = F64Var::constant(F64::from(VARIABLE_LENGTH_DOMAIN));
+    let mut padded = input.to_vec();
+    padded.push(F64Var::one());
+    padded.resize(padded.len().div_ceil(rate) * rate, F64Var::zero());
+    for (i, block) in padded.chunks(rate).enumerate() {
+        if i > 0 {
+            permute.permute(&mut state, parameters)?;
+        }
+        for (s, x) in state[capacity..].iter_mut().zip(block) {
+            *s += x;
+        }
+    }
+
+    let mut digest = Vec::with_capacity(N);
+    loop {
+        permute.permute(&mut state, parameters)?;
+        let take = rate.min(N - digest.len());
+        digest.extend_from_slice(&state[capacity..capacity + take]);
+        if digest.len() == N {
+            return Ok(EmulatedMonolithDigestVar::from_vars(digest));
+        }
+    }
+}
+
+impl<CF: PrimeField, const T: usize, const N: usize> CRHSchemeGadget<CRH64<T, N>, CF>
+    for EmulatedCRHGadget<CF, T, N>
+{
+    type InputVar = [F64Var<CF>];
+    type OutputVar = EmulatedMonolithDigestVar<N, CF>;
+    type ParametersVar = CRHParametersVar;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let cs = input.cs();
+
+        if cs.is_none() {
+            let hsh = CRH64::<T, N>::evaluate(&parameters.parameters, input.value()?)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            EmulatedMonolithDigestVar::new_constant(cs, hsh)
+        } else {
+            sponge_hash_emulated::<CF, T, N>(&parameters.parameters, input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sponge::constraints::check_setup_mode;
+    use ark_ed_on_bls12_381::Fq as CF;
+    use ark_ff::UniformRand;
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+
+    /// Checks the emulated hash of width `T` against [`CRH64`] on witness and constant inputs
+    /// around the rate block boundaries.
+    fn check_consistency<const T: usize, const N: usize>() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<T, N>::setup(&mut test_rng).unwrap();
+        let rate = T - 4;
+        for len in [0, 1, rate - 1, rate, rate + 1, 2 * rate] {
+            let inp: Vec<F64> = (0..len).map(|_| F64::rand(&mut test_rng)).collect();
+            let outp = CRH64::<T, N>::evaluate(&monolith_params, inp.as_slice()).unwrap();
+
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let params_var =
+                CRHParametersVar::new_constant(cs.clone(), monolith_params.clone()).unwrap();
+            let inp_var: Vec<F64Var<CF>> = inp
+                .iter()
+                .map(|x| F64Var::new_witness(cs.clone(), || Ok(*x)).unwrap())
+                .collect();
+            let outp_var = EmulatedCRHGadget::<CF, T, N>::evaluate(&params_var, &inp_var).unwrap();
+            assert_eq!(outp, outp_var.value().unwrap());
+
+            let expected = EmulatedMonolithDigestVar::new_witness(cs.clone(), || Ok(outp)).unwrap();
+            outp_var.enforce_equal(&expected).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            let inp_var: Vec<F64Var<CF>> = inp.iter().map(|x| F64Var::constant(*x)).collect();
+            let outp_var = EmulatedCRHGadget::<CF, T, N>::evaluate(&params_var, &inp_var).unwrap();
+            assert!(outp_var.cs().is_none());
+            assert_eq!(outp, outp_var.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_consistency() {
+        check_consistency::<8, 4>();
+        check_consistency::<12, 4>();
+        // A digest longer than the rate takes a second permutation to squeeze.
+        check_consistency::<8, 6>();
+    }
+
+    #[test]
+    fn test_setup_mode() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let inp: Vec<F64> = (0..9).map(|_| F64::rand(&mut test_rng)).collect();
+
+        let params_var =
+            CRHParametersVar::new_constant(ConstraintSystemRef::<CF>::None, monolith_params)
+                .unwrap();
+        check_setup_mode(&inp, |vars: &[F64Var<CF>]| {
+            EmulatedCRHGadget::<CF, 12>::evaluate(&params_var, vars).map(|digest| digest.0.to_vec())
+        });
+    }
+
+    #[test]
+    fn test_wrong_width_parameters() {
+        let mut test_rng = ark_std::test_rng();
+        let monolith_params = CRH64::<12>::setup(&mut test_rng).unwrap();
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let params_var = CRHParametersVar::new_constant(cs.clone(), monolith_params).unwrap();
+        let inp_var = [F64Var::new_witness(cs.clone(), || Ok(F64::from(1u64))).unwrap()];
+        let constraints = cs.num_constraints();
+        assert!(matches!(
+            EmulatedCRHGadget::<CF, 8>::evaluate(&params_var, &inp_var),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        assert_eq!(cs.num_constraints(), constraints);
+    }
+}
@@ -1,6 +1,174 @@
-use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+use crate::crh::monolith::fields::{mds_row_elements, round_constants, MonolithField};
+use crate::crh::monolith::permute::{mds_12, mds_8, MonolithPermute};
+use crate::crh::monolith::MonolithParams;
+use ark_ff::fields::{Fp2, Fp2Config, Fp64, MontBackend, MontConfig};
+use ark_ff::MontFp;
+use ark_ff::PrimeField;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
 #[derive(MontConfig)]
 #[modulus = "18446744069414584321"]
 #[generator = "7"]
 pub struct FrConfig;
 pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
+
+/// The quadratic extension `Fr[X] / (X^2 - 7)` of Goldilocks, as used by FRI-based provers over
+/// this field. An element `c0 + c1 * X` is absorbed as `c0` followed by `c1`.
+pub struct Fr2Config;
+pub type Fr2 = Fp2<Fr2Config>;
+
+impl Fp2Config for Fr2Config {
+    type Fp = Fr;
+
+    /// 7 generates the multiplicative group, so it is not a square.
+    const NONRESIDUE: Fr = MontFp!("7");
+
+    /// `NONRESIDUE^((p^i - 1) / 2)` for `i = 0, 1`.
+    const FROBENIUS_COEFF_FP2_C1: &'static [Fr] = &[MontFp!("1"), MontFp!("-1")];
+}
+
+/// The Goldilocks modulus `2^64 - 2^32 + 1`.
+pub(crate) const MODULUS: u64 = 0xFFFF_FFFF_0000_0001;
+/// `2^64 mod p`, which is `2^32 - 1`.
+pub(crate) const EPSILON: u64 = (1 << 32) - 1;
+
+/// The bar on the canonical representative of an element.
+#[inline(always)]
+pub(crate) fn bar_u64(mut ele: u64) -> u64 {
+    // All eight 8-bit S-boxes are evaluated at once on the packed word.
+    let limbl1 = ((ele & 0x8080808080808080) >> 7) | ((ele & 0x7F7F7F7F7F7F7F7F) << 1); //left rot by 1
+    let limbl2 = ((ele & 0xC0C0C0C0C0C0C0C0) >> 6) | ((ele & 0x3F3F3F3F3F3F3F3F) << 2); //left rot by 2
+    let limbl3 = ((ele & 0xE0E0E0E0E0E0E0E0) >> 5) | ((ele & 0x1F1F1F1F1F1F1F1F) << 3); //left rot by 3
+    ele ^= !limbl1 & limbl2 & limbl3;
+
+    ((ele & 0x8080808080808080) >> 7) | ((ele & 0x7F7F7F7F7F7F7F7F) << 1) //left rot by 1
+}
+
+/// Reduces a 128-bit integer to its canonical representative modulo the Goldilocks prime, using
+/// `2^64 = 2^32 - 1` and `2^96 = -1` (mod p).
+#[inline(always)]
+pub(crate) fn reduce_u128(x: u128) -> u64 {
+    let x_lo = x as u64;
+    let x_hi = (x >> 64) as u64;
+    let x_hi_hi = x_hi >> 32;
+    let x_hi_lo = x_hi & EPSILON;
+
+    let (mut t0, borrow) = x_lo.overflowing_sub(x_hi_hi);
+    if borrow {
+        // t0 is at least 2^64 - 2^32 here, so this cannot underflow.
+        t0 = t0.wrapping_sub(EPSILON);
+    }
+    let t1 = x_hi_lo * EPSILON;
+    let (t2, carry) = t0.overflowing_add(t1);
+    // t2 is below t1 <= 2^64 - 2^33 + 1 on a carry, so this cannot overflow.
+    let res = t2.wrapping_add(EPSILON * carry as u64);
+    if res >= MODULUS {
+        res - MODULUS
+    } else {
+        res
+    }
+}
+
+/// Same as [`reduce_u128`], without data-dependent branches: the borrow, the carry and the final
+/// subtraction are applied through masks hidden behind `black_box`, so that the compiler cannot
+/// turn them back into branches. Slower, and only meant for the constant-time permutation.
+#[inline(always)]
+pub(crate) fn reduce_u128_ct(x: u128) -> u64 {
+    let mask = |bit: bool| core::hint::black_box(bit as u64).wrapping_neg();
+    let x_lo = x as u64;
+    let x_hi = (x >> 64) as u64;
+    let x_hi_hi = x_hi >> 32;
+    let x_hi_lo = x_hi & EPSILON;
+
+    let (t0, borrow) = x_lo.overflowing_sub(x_hi_hi);
+    let t0 = t0.wrapping_sub(EPSILON & mask(borrow));
+    let t1 = x_hi_lo * EPSILON;
+    let (t2, carry) = t0.overflowing_add(t1);
+    let res = t2.wrapping_add(EPSILON & mask(carry));
+    // res - p wraps around exactly when res is already canonical.
+    let (reduced, below) = res.overflowing_sub(MODULUS);
+    let keep = mask(below);
+    (res & keep) | (reduced & !keep)
+}
+
+static MDS_ROW_8: [Fr; 8] = mds_row_elements(&mds_8::MDS_ROW);
+static MDS_ROW_12: [Fr; 12] = mds_row_elements(&mds_12::MDS_ROW);
+static MDS_INVERSE_ROW_8: [Fr; 8] = mds_row_elements(&mds_8::MDS_INVERSE_ROW);
+static MDS_INVERSE_ROW_12: [Fr; 12] = mds_row_elements(&mds_12::MDS_INVERSE_ROW);
+
+/// Monolith-64 for state sizes 8 and 12: eight 8-bit limbs and 4 bars per round.
+impl MonolithField for Fr {
+    const LIMB_SIZES: &'static [u32] = &[8; 8];
+    const BARS_PER_ROUND: u8 = 4;
+    const WIDTHS: &'static [usize] = &[8, 12];
+
+    fn bar(self) -> Self {
+        Fr::from(bar_u64(self.into_bigint().0[0]))
+    }
+
+    fn mds_multiply<const T: usize>(state: &mut [Self; T]) {
+        match T {
+            8 => mds_8::mds_multiply(state.as_mut().try_into().unwrap()),
+            12 => mds_12::mds_multiply(state.as_mut().try_into().unwrap()),
+            _ => unreachable!("unsupported Monolith width {T}"),
+        }
+    }
+
+    fn mds_multiply_with_rc<const T: usize>(state: &mut [Self; T], round_constants: &[Self]) {
+        match T {
+            8 => mds_8::mds_multiply_with_rc(
+                state.as_mut().try_into().unwrap(),
+                round_constants.try_into().unwrap(),
+            ),
+            12 => mds_12::mds_multiply_with_rc(
+                state.as_mut().try_into().unwrap(),
+                round_constants.try_into().unwrap(),
+            ),
+            _ => unreachable!("unsupported Monolith width {T}"),
+        }
+    }
+
+    fn permute_state<const T: usize>(state: &mut [Self; T], params: &MonolithParams<Self>) {
+        let mut raw = [0u128; T];
+        for (r, s) in raw.iter_mut().zip(state.iter()) {
+            *r = s.into_bigint().0[0] as u128;
+        }
+        MonolithPermute::<Self, T>::permute_u128(&mut raw, params);
+        for (s, r) in state.iter_mut().zip(raw) {
+            *s = Fr::from(r as u64);
+        }
+    }
+
+    fn default_round_constants(width: usize) -> &'static [&'static [u64]] {
+        match width {
+            8 => round_constants::GOLDILOCKS_8,
+            12 => round_constants::GOLDILOCKS_12,
+            _ => &[],
+        }
+    }
+
+    fn mds_row(width: usize) -> &'static [Self] {
+        match width {
+            8 => &MDS_ROW_8,
+            12 => &MDS_ROW_12,
+            _ => &[],
+        }
+    }
+
+    fn mds_inverse_row(width: usize) -> &'static [Self] {
+        match width {
+            8 => &MDS_INVERSE_ROW_8,
+            12 => &MDS_INVERSE_ROW_12,
+            _ => &[],
+        }
+    }
+
+    fn mds_matrix(width: usize) -> Vec<Vec<Self>> {
+        match width {
+            8 => mds_12::circ_mat(&mds_8::MDS_ROW),
+            12 => mds_12::circ_mat(&mds_12::MDS_ROW),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -1,6 +1,72 @@
+use crate::crh::monolith::fields::{mds_row_elements, round_constants, MonolithField};
+use crate::crh::monolith::permute::{mds_12, mds_16};
 use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
 #[derive(MontConfig)]
 #[modulus = "2147483647"]
 #[generator = "7"]
 pub struct FrConfig;
 pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
+
+static MDS_ROW_16: [Fr; 16] = mds_row_elements(&mds_16::MDS_ROW);
+static MDS_INVERSE_ROW_16: [Fr; 16] = mds_row_elements(&mds_16::MDS_INVERSE_ROW);
+
+/// Monolith-31 for a state size of 16: three 8-bit limbs and one 7-bit limb, 8 bars per round.
+///
+/// The all-ones word is a fixed point of both S-boxes, so the bar maps `[0, p)` onto itself.
+///
+/// The reference implementation also defines a width of 24. It is not supported yet: its MDS
+/// row and round constants have to come with a known-answer test against the reference, and
+/// until then asking for width 24 fails like any other unsupported width.
+impl MonolithField for Fr {
+    const LIMB_SIZES: &'static [u32] = &[8, 8, 8, 7];
+    const BARS_PER_ROUND: u8 = 8;
+    const WIDTHS: &'static [usize] = &[16];
+
+    fn mds_multiply<const T: usize>(state: &mut [Self; T]) {
+        match T {
+            16 => mds_16::mds_multiply(state.as_mut().try_into().unwrap()),
+            _ => unreachable!("unsupported Monolith width {T}"),
+        }
+    }
+
+    fn mds_multiply_with_rc<const T: usize>(state: &mut [Self; T], round_constants: &[Self]) {
+        match T {
+            16 => mds_16::mds_multiply_with_rc(
+                state.as_mut().try_into().unwrap(),
+                round_constants.try_into().unwrap(),
+            ),
+            _ => unreachable!("unsupported Monolith width {T}"),
+        }
+    }
+
+    fn default_round_constants(width: usize) -> &'static [&'static [u64]] {
+        match width {
+            16 => round_constants::MERSENNE_16,
+            _ => &[],
+        }
+    }
+
+    fn mds_row(width: usize) -> &'static [Self] {
+        match width {
+            16 => &MDS_ROW_16,
+            _ => &[],
+        }
+    }
+
+    fn mds_inverse_row(width: usize) -> &'static [Self] {
+        match width {
+            16 => &MDS_INVERSE_ROW_16,
+            _ => &[],
+        }
+    }
+
+    fn mds_matrix(width: usize) -> Vec<Vec<Self>> {
+        match width {
+            16 => mds_12::circ_mat(&mds_16::MDS_ROW),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -1,2 +1,246 @@
+use crate::crh::monolith::permute::MonolithPermute;
+use crate::crh::monolith::MonolithParams;
+use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+use ark_ff::{BigInt, BigInteger, PrimeField};
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+use sha3::digest::XofReader;
+use sha3::Shake128Reader;
+
 pub mod goldilocks;
 pub mod mersenne;
+mod round_constants;
+
+/// A prime field Monolith can be instantiated over.
+///
+/// The field supplies everything that depends on the modulus: how the bar decomposes an element
+/// into limbs, the MDS matrices for the state sizes it supports, and how round constants are
+/// sampled from SHAKE128. Everything else (bricks, round structure, sponge) is shared.
+pub trait MonolithField: PrimeField {
+    /// Bit sizes of the limbs the bar splits a canonical element into, least significant first.
+    /// 8-bit limbs go through the cubic S-box, 7-bit limbs through the quadratic one.
+    const LIMB_SIZES: &'static [u32];
+    /// Number of state elements that go through a bar in every round.
+    const BARS_PER_ROUND: u8;
+    /// State sizes with an MDS matrix over this field. `MonolithPermute` refuses to compile for
+    /// any other width.
+    const WIDTHS: &'static [usize];
+
+    /// The bar: decompose into limbs, apply the S-box to every limb and recompose.
+    fn bar(self) -> Self {
+        let ele = self.into_bigint().as_ref()[0];
+        let mut out = 0u64;
+        let mut offset = 0;
+        for bits in Self::LIMB_SIZES {
+            let limb = ((ele >> offset) & ((1 << bits) - 1)) as u8;
+            out |= (s_box(limb, *bits) as u64) << offset;
+            offset += bits;
+        }
+        Self::from(out)
+    }
+
+    /// The inverse of the bar: decompose into limbs, apply the inverse S-box to every limb and
+    /// recompose. Only defined on the image of the bar, which for the supported fields is the
+    /// whole field.
+    fn bar_inverse(self) -> Self {
+        let ele = self.into_bigint().as_ref()[0];
+        let mut out = 0u64;
+        let mut offset = 0;
+        for bits in Self::LIMB_SIZES {
+            let limb = ((ele >> offset) & ((1 << bits) - 1)) as u8;
+            out |= (s_box_inverse(limb, *bits) as u64) << offset;
+            offset += bits;
+        }
+        Self::from(out)
+    }
+
+    /// Multiplies `state` by the MDS matrix of width `T`, which must be one of `WIDTHS`.
+    fn mds_multiply<const T: usize>(state: &mut [Self; T]);
+
+    /// Multiplies `state` by the MDS matrix of width `T`, which must be one of `WIDTHS`, and adds
+    /// `round_constants`.
+    fn mds_multiply_with_rc<const T: usize>(state: &mut [Self; T], round_constants: &[Self]);
+
+    /// Runs the permutation on `state`, which holds exactly `T` elements. Fields with a faster
+    /// representation than their field elements override this; by default the rounds are
+    /// evaluated with field arithmetic.
+    fn permute_state<const T: usize>(state: &mut [Self; T], params: &MonolithParams<Self>) {
+        MonolithPermute::<Self, T>::permute_reference(state, params);
+    }
+
+    /// The first row of the circulant MDS matrix of width `width`, from a static table. Row `r`
+    /// of the matrix is this row rotated right by `r`. Empty for widths not in `WIDTHS`.
+    fn mds_row(width: usize) -> &'static [Self];
+
+    /// The first row of the inverse of the MDS matrix of width `width`, from a static table. The
+    /// inverse of a circulant matrix is circulant, so row `r` is this row rotated right by `r`.
+    /// Empty for widths not in `WIDTHS`.
+    fn mds_inverse_row(width: usize) -> &'static [Self];
+
+    /// The MDS matrix of width `width`, row by row.
+    fn mds_matrix(width: usize) -> Vec<Vec<Self>>;
+
+    /// The inverse of the MDS matrix of width `width`, row by row, computed by Gauss-Jordan
+    /// elimination.
+    fn mds_inverse_matrix(width: usize) -> Vec<Vec<Self>> {
+        let mut mat = Self::mds_matrix(width);
+        let mut inv: Vec<Vec<Self>> = (0..width)
+            .map(|i| {
+                let mut row = vec![Self::zero(); width];
+                row[i] = Self::one();
+                row
+            })
+            .collect();
+        for col in 0..width {
+            // Every square submatrix of an MDS matrix is non-singular, so the pivot never is zero.
+            let pivot_inv = mat[col][col]
+                .inverse()
+                .expect("MDS matrices have non-zero diagonal entries");
+            for (m, i) in mat[col].iter_mut().zip(inv[col].iter_mut()) {
+                *m *= pivot_inv;
+                *i *= pivot_inv;
+            }
+            for row in 0..width {
+                if row == col || mat[row][col].is_zero() {
+                    continue;
+                }
+                let factor = mat[row][col];
+                for k in 0..width {
+                    let (m, i) = (mat[col][k], inv[col][k]);
+                    mat[row][k] -= factor * m;
+                    inv[row][k] -= factor * i;
+                }
+            }
+        }
+        inv
+    }
+
+    /// The precomputed round constants of the standard instance with a state of `width`
+    /// elements, as canonical representatives, one row for each round but the last.
+    fn default_round_constants(width: usize) -> &'static [&'static [u64]];
+
+    /// Samples a round constant from `reader` by rejection sampling: read as many little-endian
+    /// bytes as the modulus occupies and retry until the value is below the modulus.
+    fn sample_round_constant(reader: &mut Shake128Reader) -> Self {
+        let num_bytes = (Self::MODULUS_BIT_SIZE as usize).div_ceil(8);
+        loop {
+            let mut bytes = [0u8; 8];
+            reader.read(&mut bytes[..num_bytes]);
+            if let Some(ele) = Self::from_bigint(u64::from_le_bytes(bytes).into()) {
+                return ele;
+            }
+        }
+    }
+}
+
+/// Whether `width` is one of `widths`. Usable in constant contexts, where `contains` is not.
+pub(crate) const fn is_supported_width(widths: &[usize], width: usize) -> bool {
+    let mut i = 0;
+    while i < widths.len() {
+        if widths[i] == width {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// The number of high bits of `p - 1` that are ones, if the bits below them are all zero, as for
+/// both Monolith fields. An integer of `MODULUS_BIT_SIZE` bits is then below `p` unless its high
+/// bits are all ones and its low bits are not all zero, which the circuit and the AIR check with
+/// a few constraints instead of a bitwise comparison.
+pub(crate) fn modulus_high_ones<F: PrimeField>() -> Option<usize> {
+    let max = (-F::one()).into_bigint();
+    let bits = F::MODULUS_BIT_SIZE as usize;
+    let ones = (0..bits).rev().take_while(|i| max.get_bit(*i)).count();
+    (0..bits - ones).all(|i| !max.get_bit(i)).then_some(ones)
+}
+
+/// Converts the first row of an MDS matrix or of its inverse to field elements at compile time,
+/// for the tables behind [`MonolithField::mds_row`] and [`MonolithField::mds_inverse_row`].
+pub(crate) const fn mds_row_elements<C: MontConfig<1>, const T: usize>(
+    row: &[u64; T],
+) -> [Fp64<MontBackend<C, 1>>; T] {
+    let mut out = [Fp64::<MontBackend<C, 1>>::new(BigInt([0])); T];
+    let mut i = 0;
+    while i < T {
+        out[i] = Fp64::<MontBackend<C, 1>>::new(BigInt([row[i]]));
+        i += 1;
+    }
+    out
+}
+
+/// The chi-like S-box of Monolith on a limb of `bits` bits.
+///
+/// For 8-bit limbs this is `(y ^ (!(y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1`, for 7-bit limbs
+/// `(y ^ (!(y <<< 1) & (y <<< 2))) <<< 1`, where `<<<` rotates within the limb.
+pub const fn s_box(limb: u8, bits: u32) -> u8 {
+    match bits {
+        8 => (limb ^ (!limb.rotate_left(1) & limb.rotate_left(2) & limb.rotate_left(3)))
+            .rotate_left(1),
+        7 => rotate_left_7(
+            (limb ^ (!rotate_left_7(limb, 1) & rotate_left_7(limb, 2))) & 0x7F,
+            1,
+        ),
+        _ => panic!("Monolith S-boxes are only defined on 7- and 8-bit limbs"),
+    }
+}
+
+/// The input bits that bit `i` of the [`s_box`] output on a limb of `bits` bits depends on, for
+/// evaluating the S-box bitwise in a circuit or an AIR. The output bit is
+/// `y[xor] ^ (!y[negated] & y[and] & y[and_8])`, without the last factor for 7-bit limbs.
+pub(crate) struct SBoxTaps {
+    pub(crate) xor: usize,
+    pub(crate) negated: usize,
+    pub(crate) and: usize,
+    pub(crate) and_8: Option<usize>,
+}
+
+impl SBoxTaps {
+    pub(crate) fn new(bits: usize, i: usize) -> Self {
+        // The final rotation by one moves bit i - 1 of the chi output to bit i, and bit j of
+        // (y <<< k) is bit (j - k) of y.
+        let j = (i + bits - 1) % bits;
+        let rot = |k: usize| (j + bits - k) % bits;
+        Self {
+            xor: j,
+            negated: rot(1),
+            and: rot(2),
+            and_8: (bits == 8).then(|| rot(3)),
+        }
+    }
+}
+
+/// Rotates the 7-bit limb `y` left by `r < 7` bits.
+const fn rotate_left_7(y: u8, r: u32) -> u8 {
+    ((y << r) | (y >> (7 - r))) & 0x7F
+}
+
+/// The inverse of [`s_box`] on a limb of `bits` bits.
+pub fn s_box_inverse(limb: u8, bits: u32) -> u8 {
+    match bits {
+        8 => S_BOX_INVERSE_8[limb as usize],
+        7 => S_BOX_INVERSE_7[limb as usize],
+        _ => panic!("Monolith S-boxes are only defined on 7- and 8-bit limbs"),
+    }
+}
+
+const S_BOX_INVERSE_8: [u8; 256] = s_box_inverse_table();
+const S_BOX_INVERSE_7: [u8; 128] = s_box_inverse_table();
+
+/// Tabulates the inverse of the S-box on `L`-entry limbs by evaluating it on every limb. Fails
+/// const evaluation if the S-box is not a permutation.
+const fn s_box_inverse_table<const L: usize>() -> [u8; L] {
+    let bits = L.trailing_zeros();
+    let mut table = [0u8; L];
+    let mut seen = [false; L];
+    let mut limb = 0;
+    while limb < L {
+        let image = s_box(limb as u8, bits) as usize;
+        assert!(!seen[image], "the S-box is not a permutation");
+        seen[image] = true;
+        table[image] = limb as u8;
+        limb += 1;
+    }
+    table
+}
@@ -0,0 +1,160 @@
+//! Precomputed round constants of the standard Monolith instances.
+//!
+//! Each table holds the canonical representatives of the constants of the first five rounds, one
+//! row per round; the sixth round has none. They are the output of the SHAKE128 derivation of the
+//! reference implementation, `MonolithParams::from_seed(b"Monolith")`, which the tests re-run.
+
+/// Monolith-64 with a state of 8 elements.
+pub(crate) const GOLDILOCKS_8: &[&[u64]] = &[
+    &[
+        16247657010527959352,
+        3507341496370419234,
+        12986194972226691144,
+        13243872069887723420,
+        16468357641549368339,
+        6269510718399009150,
+        6783020747541032855,
+        8294350332713351371,
+    ],
+    &[
+        9320936503255354367,
+        14251412441843052930,
+        17491509512888830897,
+        12736700943799519351,
+        11596096110565786530,
+        16867432666032818301,
+        14621838757525000458,
+        5309238115328529065,
+    ],
+    &[
+        6848259424028922199,
+        11536213859200672197,
+        12649922143116771506,
+        5439448048615575904,
+        16291170983163463236,
+        16341549610642192450,
+        16349921770106162732,
+        14943262463155389851,
+    ],
+    &[
+        14446932734031609072,
+        3735712625733861496,
+        1930858825874578566,
+        16340179516748881854,
+        1920381666062862052,
+        17844728832468394559,
+        17263012147613388504,
+        14537818064995220684,
+    ],
+    &[
+        15443225644728171840,
+        1533890869557709600,
+        11223567746539997113,
+        10849671395254288924,
+        3257282833733138049,
+        11139291983387289124,
+        16580220587904809662,
+        1722121024065536437,
+    ],
+];
+
+/// Monolith-64 with a state of 12 elements.
+pub(crate) const GOLDILOCKS_12: &[&[u64]] = &[
+    &[
+        13596126580325903823,
+        5676126986831820406,
+        11349149288412960427,
+        3368797843020733411,
+        16240671731749717664,
+        9273190757374900239,
+        14446552112110239438,
+        4033077683985131644,
+        4291229347329361293,
+        13231607645683636062,
+        1383651072186713277,
+        8898815177417587567,
+    ],
+    &[
+        2383619671172821638,
+        6065528368924797662,
+        16737578966352303081,
+        2661700069680749654,
+        7414030722730336790,
+        18124970299993404776,
+        9169923000283400738,
+        15832813151034110977,
+        16245117847613094506,
+        11056181639108379773,
+        10546400734398052938,
+        8443860941261719174,
+    ],
+    &[
+        15799082741422909885,
+        13421235861052008152,
+        15448208253823605561,
+        2540286744040770964,
+        2895626806801935918,
+        8644593510196221619,
+        17722491003064835823,
+        5166255496419771636,
+        1015740739405252346,
+        4400043467547597488,
+        5176473243271652644,
+        4517904634837939508,
+    ],
+    &[
+        18341030605319882173,
+        13366339881666916534,
+        6291492342503367536,
+        10004214885638819819,
+        4748655089269860551,
+        1520762444865670308,
+        8393589389936386108,
+        11025183333304586284,
+        5993305003203422738,
+        458912836931247573,
+        5947003897778655410,
+        17184667486285295106,
+    ],
+    &[
+        15710528677110011358,
+        8929476121507374707,
+        2351989866172789037,
+        11264145846854799752,
+        14924075362538455764,
+        10107004551857451916,
+        18325221206052792232,
+        16751515052585522105,
+        15305034267720085905,
+        15639149412312342017,
+        14624541102106656564,
+        3542311898554959098,
+    ],
+];
+
+/// Monolith-31 with a state of 16 elements.
+pub(crate) const MERSENNE_16: &[&[u64]] = &[
+    &[
+        1033436816, 348863691, 2081103763, 994924237, 64925253, 677331122, 1735246508, 26616398,
+        1538025930, 1710098735, 995978747, 1336376181, 2051827886, 447361871, 1829769948,
+        718914942,
+    ],
+    &[
+        474392908, 549190350, 140657697, 642927328, 325988066, 2087527882, 1429283917, 537644603,
+        2072852575, 707584548, 482862777, 829305883, 1016581262, 148132697, 397768408, 50011713,
+    ],
+    &[
+        897025585, 597857797, 389941735, 1101342757, 1318622762, 1954712215, 1789281623, 529033351,
+        913202249, 1707514131, 616819674, 197082924, 1180366701, 241453365, 1700285697, 1755996717,
+    ],
+    &[
+        1917698553, 1252360787, 1273610561, 212500927, 1268578595, 1403584286, 612974258,
+        1024938353, 1546879084, 1752198737, 757476618, 916242693, 1739315286, 1012279900,
+        1254788910, 1865871347,
+    ],
+    &[
+        534908981, 1994856941, 1598293579, 510970053, 1868253334, 1194878847, 360986778,
+        1303396410, 337495830, 1233499389, 1058246115, 1413610001, 799568848, 48161847, 1339121921,
+        1110912837,
+    ],
+];
@@ -1,164 +1,299 @@
 use core::ops::AddAssign;
 
-use super::mds_12;
-use crate::crh::monolith::fields::goldilocks::Fr as FP64;
-use crate::crh::monolith::fields::goldilocks::FrConfig;
+use crate::crh::monolith::fields::{modulus_high_ones, MonolithField, SBoxTaps};
+use crate::crh::monolith::permute::MonolithPermute;
 use crate::crh::monolith::MonolithParams;
-use ark_ff::MontBackend;
-use ark_r1cs_std::fields::fp::FpVar;
+use crate::sponge::constraints::PermutationGadget;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::fields::fp::{AllocatedFp, FpVar};
 use ark_r1cs_std::prelude::*;
-use ark_relations::r1cs::SynthesisError;
-pub struct MonolithPermuteVar<const T: usize>;
-impl<const T: usize> MonolithPermuteVar<T> {
-    #[tracing::instrument(target = "r1cs", skip(self))]
-    fn s(&self, byt: UInt8<FP64>) -> Result<UInt8<FP64>, SynthesisError> {
-        let b1 = !byt.rotate_left(1);
-        let b2 = byt.rotate_left(2);
-        let b3 = byt.rotate_left(3);
-        let b = byt.clone() ^ (b1 & b2 & b3);
-        Ok(b.rotate_left(1))
+use ark_relations::r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+use ark_std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// The S-box on a single limb given by its little-endian bits; see
+/// [`s_box`](crate::crh::monolith::fields::s_box). The bits may live in any constraint field, so
+/// the emulated gadgets share it.
+#[tracing::instrument(target = "r1cs")]
+pub(crate) fn s_box_var<CF: PrimeField>(
+    limb: &[Boolean<CF>],
+) -> Result<Vec<Boolean<CF>>, SynthesisError> {
+    (0..limb.len())
+        .map(|i| {
+            let taps = SBoxTaps::new(limb.len(), i);
+            let mut factors = vec![!limb[taps.negated].clone(), limb[taps.and].clone()];
+            factors.extend(taps.and_8.map(|k| limb[k].clone()));
+            Ok(&limb[taps.xor] ^ &Boolean::kary_and(&factors)?)
+        })
+        .collect()
+}
+
+/// R1CS gadget for [`MonolithPermute`](super::MonolithPermute) over the field `F` with a state
+/// of `T` elements.
+///
+/// The gadget holds no data: the MDS layers borrow the static first row of the matrix from
+/// [`MonolithField::mds_row`], so creating one per permutation costs nothing.
+pub struct MonolithPermuteVar<F: MonolithField, const T: usize> {
+    field_phantom: PhantomData<F>,
+}
+
+impl<F: MonolithField, const T: usize> Default for MonolithPermuteVar<F, T> {
+    fn default() -> Self {
+        let () = MonolithPermute::<F, T>::WIDTH_CHECK;
+        Self {
+            field_phantom: PhantomData,
+        }
     }
+}
+impl<F: MonolithField, const T: usize> MonolithPermuteVar<F, T> {
+    /// The bar on a single element; see [`MonolithField::bar`].
+    ///
+    /// The element is decomposed into bits once, all limbs go through their S-box on those bits,
+    /// and the output is recomposed for free. With the canonical decomposition from
+    /// [`Self::to_canonical_bits`] this costs 260 constraints over Goldilocks and 121 over
+    /// Mersenne-31. Constants stay constant.
     #[tracing::instrument(target = "r1cs", skip(self))]
-    pub fn bar(&self, element: FpVar<FP64>) -> Result<FpVar<FP64>, SynthesisError> {
-        let mut byts = <FpVar<FP64> as ToBytesGadget<_>>::to_bytes_le(&element)?;
-        for byt in &mut byts {
-            *byt = self.s(byt.clone())?;
+    pub fn bar(&self, element: FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+        if let FpVar::Constant(c) = element {
+            return Ok(FpVar::Constant(c.bar()));
+        }
+        let bits = Self::to_canonical_bits(&element)?;
+        let mut out = Vec::with_capacity(bits.len());
+        let mut offset = 0;
+        for size in F::LIMB_SIZES {
+            let size = *size as usize;
+            out.extend(s_box_var(&bits[offset..offset + size])?);
+            offset += size;
         }
-        let ele = Boolean::le_bits_to_fp(byts.to_bits_le()?.as_slice())?;
-        Ok(ele)
-        // let mut bts = <FpVar<FP64> as ToBitsGadget<_>>::to_bits_le(&element)?;
-        // let l1
-        // let rot1_1 = Boolean::<FP64>::constant_vec_from_bytes(&0x8080808080808080u64.to_le_bytes());
-        // let rot1_2 = Boolean::<FP64>::constant_vec_from_bytes(&0x7F7F7F7F7F7F7F7Fu64.to_le_bytes());
-        // let rot2_1 = Boolean::<FP64>::constant_vec_from_bytes(&0xC0C0C0C0C0C0C0C0u64.to_le_bytes());
-        // let rot2_2 = Boolean::<FP64>::constant_vec_from_bytes(&0x3F3F3F3F3F3F3F3Fu64.to_le_bytes());
-        // let limb1: Vec<Boolean<FP64>> = bts.iter().zip(rot1_1).map(|(b, r)| b & r).collect();
-        // Ok(FpVar::<FP64>::zero())
+        // The bar maps canonical elements to canonical elements, so the output bits need no
+        // range check of their own.
+        Ok(Self::recompose(&out))
+    }
+
+    /// Decomposes `element` into the `MODULUS_BIT_SIZE` little-endian bits of its canonical
+    /// representative: one constraint per bit, one for the recomposition and the range check of
+    /// [`Self::enforce_canonical`].
+    fn to_canonical_bits(element: &FpVar<F>) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let cs = element.cs();
+        let value = element.value().ok().map(|v| v.into_bigint());
+        let bits = (0..F::MODULUS_BIT_SIZE as usize)
+            .map(|i| {
+                Boolean::new_witness(cs.clone(), || {
+                    value
+                        .map(|v| v.get_bit(i))
+                        .ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::recompose(&bits).enforce_equal(element)?;
+        Self::enforce_canonical(&bits)?;
+        Ok(bits)
     }
+
+    /// The field element with little-endian bits `bits`, as a linear combination of the bits.
+    fn recompose(bits: &[Boolean<F>]) -> FpVar<F> {
+        let mut power = F::one();
+        bits.iter()
+            .map(|bit| {
+                let term = FpVar::from(bit.clone()) * power;
+                power.double_in_place();
+                term
+            })
+            .sum()
+    }
+
+    /// Enforces that the integer with little-endian `bits`, which has `MODULUS_BIT_SIZE` bits, is
+    /// below the modulus: in three constraints if [`modulus_high_ones`] allows, otherwise with the
+    /// bitwise comparison.
+    fn enforce_canonical(bits: &[Boolean<F>]) -> Result<(), SynthesisError> {
+        let ones = match modulus_high_ones::<F>() {
+            Some(ones) => ones,
+            None => {
+                Boolean::enforce_smaller_or_equal_than_le(bits, (-F::one()).into_bigint())?;
+                return Ok(());
+            }
+        };
+        let low = bits.len() - ones;
+        let high_sum: FpVar<F> = bits[low..].iter().map(|b| FpVar::from(b.clone())).sum();
+        let high_all_ones = (high_sum - F::from(ones as u64)).is_zero()?;
+        FpVar::from(high_all_ones).mul_equals(&Self::recompose(&bits[..low]), &FpVar::zero())
+    }
+
     #[tracing::instrument(target = "r1cs", skip(self))]
     pub fn bars(
         &self,
-        input: [FpVar<FP64>; T],
-        params: MonolithParams,
-    ) -> Result<[FpVar<FP64>; T], SynthesisError> {
-        let mut out_bars: Vec<FpVar<FP64>> = vec![];
-        for (ind, ele) in input.iter().enumerate().into_iter() {
-            if ind >= params.bar_per_round.into() {
-                out_bars.push(ele.clone());
-                continue;
-            }
-
-            out_bars.push(self.bar(ele.clone()).expect("Err"));
+        input: [FpVar<F>; T],
+        params: &MonolithParams<F>,
+    ) -> Result<[FpVar<F>; T], SynthesisError> {
+        let mut out = input;
+        for ele in out.iter_mut().take(params.bar_per_round.into()) {
+            *ele = self.bar(ele.clone())?;
         }
-        Ok(out_bars.try_into().unwrap())
+        Ok(out)
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
-    pub fn bricks(&self, input: &mut [FpVar<FP64>]) -> Result<(), SynthesisError> {
+    pub fn bricks(&self, input: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
         for i in (1..input.len()).rev() {
-            let m = input[i - 1].square().expect("Err");
+            let m = input[i - 1].square()?;
             input[i].add_assign(m);
         }
         Ok(())
     }
+    /// Multiplies `input` by the MDS matrix. Free in R1CS; see [`Self::mds_layer`].
     #[tracing::instrument(target = "r1cs", skip(self))]
-    pub fn concrete(&self, input: &mut [FpVar<FP64>]) -> Result<(), SynthesisError> {
-        if T == 12 {
-            let row = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
-            let mds = mds_12::circ_mat::<MontBackend<FrConfig, 1>>(&row);
-            let mut out = Vec::new();
-            for row in 0..T {
-                let mut cur = FpVar::<FP64>::zero();
-                for (col, inp) in input.iter().enumerate().take(T) {
-                    let term = inp * mds[row][col];
-                    cur += term;
-                }
-                out.push(cur);
-            }
-            input.clone_from_slice(&out[..T])
-        } else if T == 8 {
-            let row = [23, 8, 13, 10, 7, 6, 21, 8];
-            let mds = mds_12::circ_mat::<MontBackend<FrConfig, 1>>(&row);
-            let mut out = Vec::new();
-            for row in 0..T {
-                let mut cur = FpVar::<FP64>::zero();
-                for (col, inp) in input.iter().enumerate().take(T) {
-                    let term = inp * mds[row][col];
-                    cur += term;
-                }
-                out.push(cur);
-            }
-            input.clone_from_slice(&out[..T]);
-        }
-        Ok(())
+    pub fn concrete(&self, input: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
+        self.mds_layer(input, None)
     }
+    /// Multiplies `input` by the MDS matrix and adds `round_constants`. Free in R1CS; see
+    /// [`Self::mds_layer`].
     #[tracing::instrument(target = "r1cs", skip(self))]
     pub fn concrete_wrc(
         &self,
-        input: &mut [FpVar<FP64>],
-        round_constants: &[FP64],
+        input: &mut [FpVar<F>],
+        round_constants: &[F],
     ) -> Result<(), SynthesisError> {
-        if T == 12 {
-            let row = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
-            let mds = mds_12::circ_mat::<MontBackend<FrConfig, 1>>(&row);
-            let mut out = Vec::new();
-            for row in 0..T {
-                let mut cur = FpVar::<FP64>::zero();
-                cur += round_constants[row];
-                for (col, inp) in input.iter().enumerate().take(T) {
-                    let term = inp * mds[row][col];
-                    cur += term;
+        let round_constants: Vec<FpVar<F>> = round_constants
+            .iter()
+            .map(|rc| FpVar::Constant(*rc))
+            .collect();
+        self.mds_layer(input, Some(&round_constants))
+    }
+
+    /// The MDS layer, with `round_constants` added if there are any. The round constants may be
+    /// variables, as in [`Self::permute_with_round_constants`].
+    ///
+    /// Every output element is emitted as a single linear combination of the input variables and
+    /// the constant term, so the layer adds no constraints and one symbolic linear combination per
+    /// element, instead of one per product and sum. Outputs of constant inputs stay constant.
+    fn mds_layer(
+        &self,
+        input: &mut [FpVar<F>],
+        round_constants: Option<&[FpVar<F>]>,
+    ) -> Result<(), SynthesisError> {
+        if input.len() != T || round_constants.is_some_and(|rc| rc.len() != T) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let cs = input
+            .cs()
+            .or(round_constants.map_or(ConstraintSystemRef::None, |rc| rc.cs()));
+        let row = F::mds_row(T);
+        let out = (0..T)
+            .map(|i| {
+                let mut constant = F::zero();
+                let mut lc = LinearCombination::zero();
+                let mut value = Some(F::zero());
+                let round_constant = round_constants.map(|rc| (&rc[i], F::one()));
+                // Row i of the circulant matrix is the first row rotated right by i.
+                let coefficients = (0..T).map(|c| row[(c + T - i) % T]);
+                let terms = input.iter().zip(coefficients).chain(round_constant);
+                for (inp, m) in terms {
+                    match inp {
+                        FpVar::Constant(c) => constant += m * c,
+                        FpVar::Var(v) => {
+                            lc += (m, v.variable);
+                            value = value.zip(v.value().ok()).map(|(acc, x)| acc + m * x);
+                        }
+                    }
                 }
-                out.push(cur);
-            }
-            input.clone_from_slice(&out[..T]);
-        } else if T == 8 {
-            let row = [23, 8, 13, 10, 7, 6, 21, 8];
-            let mds = mds_12::circ_mat::<MontBackend<FrConfig, 1>>(&row);
-            let mut out = Vec::new();
-            for row in 0..T {
-                let mut cur = FpVar::<FP64>::zero();
-                cur += round_constants[row];
-                for (col, inp) in input.iter().enumerate().take(T) {
-                    let term = inp * mds[row][col];
-                    cur += term;
+                if cs.is_none() {
+                    return Ok(FpVar::Constant(constant));
                 }
-                out.push(cur);
-            }
-            input.clone_from_slice(&out[..T]);
-        }
+                lc += (constant, Variable::One);
+                let variable = cs.new_lc(lc)?;
+                Ok(FpVar::Var(AllocatedFp::new(
+                    value.map(|v| v + constant),
+                    variable,
+                    cs.clone(),
+                )))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        input.clone_from_slice(&out);
         Ok(())
     }
+    /// Runs the permutation on `input`, which holds exactly `T` elements. An input or parameters
+    /// for another width fail with [`SynthesisError::Unsatisfiable`] before any constraint is
+    /// added.
     pub fn permute(
         &self,
-        input: &mut [FpVar<FP64>],
-        params: &MonolithParams,
+        input: &mut [FpVar<F>],
+        params: &MonolithParams<F>,
     ) -> Result<(), SynthesisError> {
-        let mut out: [FpVar<FP64>; T] = input
-            .to_vec()
-            .try_into()
-            .expect("array size does not match");
+        if input.len() != T || params.state_size() != T {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut out: [FpVar<F>; T] = core::array::from_fn(|i| input[i].clone());
         self.concrete(&mut out)?;
         for rc in params.round_constants.iter() {
-            out = self.bars(out, params.clone())?;
+            out = self.bars(out, params)?;
             self.bricks(&mut out)?;
             self.concrete_wrc(&mut out, rc)?;
         }
         input.clone_from_slice(&out[..T]);
         Ok(())
     }
+
+    /// Runs the permutation with round constants that are circuit variables, for instance the
+    /// public inputs of a
+    /// [`CRHParametersInputVar`](crate::crh::monolith::constraints::CRHParametersInputVar).
+    /// `params` supplies everything else; `round_constants` needs a row of `T` elements for each
+    /// of its rounds. Adding the round constants stays free.
+    ///
+    /// Like [`Self::permute`], fails with [`SynthesisError::Unsatisfiable`] before adding any
+    /// constraint if `input` or `params` are for another width than `T`, or if `round_constants`
+    /// has another number of rows than `params`. These are misuses of the gadget, not false
+    /// statements, so the constraint system stays as it was.
+    pub fn permute_with_round_constants(
+        &self,
+        input: &mut [FpVar<F>],
+        params: &MonolithParams<F>,
+        round_constants: &[Vec<FpVar<F>>],
+    ) -> Result<(), SynthesisError> {
+        if input.len() != T
+            || params.state_size() != T
+            || round_constants.len() != params.round_constants.len()
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut out: [FpVar<F>; T] = core::array::from_fn(|i| input[i].clone());
+        self.concrete(&mut out)?;
+        for rc in round_constants {
+            out = self.bars(out, params)?;
+            self.bricks(&mut out)?;
+            self.mds_layer(&mut out, Some(rc))?;
+        }
+        input.clone_from_slice(&out);
+        Ok(())
+    }
+}
+
+impl<F: MonolithField, const T: usize> PermutationGadget<F> for MonolithPermuteVar<F, T> {
+    type Native = MonolithPermute<F, T>;
+
+    fn permute(
+        state: &mut [FpVar<F>],
+        parameters: &MonolithParams<F>,
+    ) -> Result<(), SynthesisError> {
+        Self::default().permute(state, parameters)
+    }
 }
 
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::crh::monolith::permute::MonolithPermute;
-    use crate::crh::{monolith::CRH64, CRHScheme};
-    use ark_ff::UniformRand;
-    use rand::thread_rng;
+    use crate::crh::monolith::fields::goldilocks::Fr as FP64;
+    use crate::crh::monolith::fields::mersenne::Fr as F31;
+    use crate::crh::{
+        monolith::{CRH31, CRH64},
+        CRHScheme,
+    };
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
     #[test]
     pub fn simple_permute() {
-        let mut rng = thread_rng();
+        let mut rng = test_rng();
         let mut inp: [FP64; 12] = [FP64::rand(&mut rng); 12];
         let mut inp_var: [FpVar<FP64>; 12] = [
             FpVar::<FP64>::zero() + inp[0],
@@ -175,9 +310,179 @@ pub mod test {
             FpVar::<FP64>::zero() + inp[0],
         ];
         let params = CRH64::<12>::setup(&mut rng).unwrap();
-        MonolithPermute::<12>::permute(&mut inp, &params);
-        let v = MonolithPermuteVar::<12>;
+        MonolithPermute::<FP64, 12>::permute(&mut inp, &params).unwrap();
+        let v = MonolithPermuteVar::<FP64, 12>::default();
         let _ = v.permute(&mut inp_var, &params);
         assert_eq!(inp_var.value(), Ok(inp));
     }
+    #[test]
+    pub fn permute_31() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<F31>::new_ref();
+        let mut inp: [F31; 16] = core::array::from_fn(|_| F31::rand(&mut rng));
+        let mut inp_var = inp
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let params = CRH31::<16>::setup(&mut rng).unwrap();
+        MonolithPermute::<F31, 16>::permute(&mut inp, &params).unwrap();
+        let v = MonolithPermuteVar::<F31, 16>::default();
+        v.permute(&mut inp_var, &params).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(inp_var.value().unwrap(), inp);
+    }
+    #[test]
+    pub fn permutation_consistency() {
+        use crate::sponge::constraints::check_permutation_consistency;
+
+        let mut rng = test_rng();
+        check_permutation_consistency::<FP64, MonolithPermuteVar<FP64, 8>>(
+            &CRH64::<8>::setup(&mut rng).unwrap(),
+        );
+        check_permutation_consistency::<FP64, MonolithPermuteVar<FP64, 12>>(
+            &CRH64::<12>::setup(&mut rng).unwrap(),
+        );
+        check_permutation_consistency::<F31, MonolithPermuteVar<F31, 16>>(
+            &CRH31::<16>::setup(&mut rng).unwrap(),
+        );
+    }
+
+    #[test]
+    pub fn permute_wrong_sizes() {
+        let mut rng = test_rng();
+        let v = MonolithPermuteVar::<FP64, 12>::default();
+        let params = CRH64::<12>::setup(&mut rng).unwrap();
+        let mut state = vec![FpVar::<FP64>::zero(); 11];
+        assert_eq!(
+            v.permute(&mut state, &params),
+            Err(SynthesisError::Unsatisfiable)
+        );
+        let mut state = vec![FpVar::<FP64>::zero(); 12];
+        let params = CRH64::<8>::setup(&mut rng).unwrap();
+        assert_eq!(
+            v.permute(&mut state, &params),
+            Err(SynthesisError::Unsatisfiable)
+        );
+    }
+
+    /// Checks the bar gadget against the native bar on `values`, and that it costs `expected`
+    /// constraints per element.
+    fn check_bar<F: MonolithField, const T: usize>(values: &[F], expected: usize) {
+        let v = MonolithPermuteVar::<F, T>::default();
+        for value in values {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let ele = FpVar::new_witness(cs.clone(), || Ok(*value)).unwrap();
+            let out = v.bar(ele).unwrap();
+            assert_eq!(out.value().unwrap(), value.bar());
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(cs.num_constraints(), expected);
+
+            let out = v.bar(FpVar::Constant(*value)).unwrap();
+            assert!(out.is_constant());
+            assert_eq!(out.value().unwrap(), value.bar());
+        }
+    }
+
+    #[test]
+    pub fn bar_consistency() {
+        let mut rng = test_rng();
+        let mut values = vec![
+            FP64::zero(),
+            FP64::one(),
+            -FP64::one(),
+            -FP64::from(2u64),
+            FP64::from(u32::MAX as u64),
+            FP64::from(1u64 << 32),
+            FP64::from(u64::MAX - (1 << 32)),
+        ];
+        values.extend((0..20).map(|_| FP64::rand(&mut rng)));
+        check_bar::<FP64, 12>(&values, 260);
+
+        let mut values = vec![F31::zero(), F31::one(), -F31::one(), F31::from(1u64 << 30)];
+        values.extend((0..20).map(|_| F31::rand(&mut rng)));
+        check_bar::<F31, 16>(&values, 121);
+    }
+
+    /// Checks the range check of the decomposition on the 64-bit integer `x`.
+    fn canonical(x: u64) -> bool {
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let bits = (0..64)
+            .map(|i| Boolean::new_witness(cs.clone(), || Ok((x >> i) & 1 == 1)).unwrap())
+            .collect::<Vec<_>>();
+        MonolithPermuteVar::<FP64, 12>::enforce_canonical(&bits).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    /// The decomposition must be canonical: `x + p` has the same field value as `x` but other
+    /// bits, which would give another bar output if it were accepted.
+    #[test]
+    pub fn canonical_decomposition() {
+        const P: u64 = 0xFFFF_FFFF_0000_0001;
+        for x in [0, 1, 5, u32::MAX as u64, 1 << 32, P - 2, P - 1] {
+            assert!(canonical(x), "{x} is canonical");
+        }
+        for x in [P, P + 5, u64::MAX - 1, u64::MAX] {
+            assert!(!canonical(x), "{x} is not canonical");
+        }
+    }
+
+    /// Checks the MDS layer of width `T` against the native one, and that it is made of linear
+    /// combinations only.
+    fn check_concrete<F: MonolithField, const T: usize>() {
+        let mut rng = test_rng();
+        let mut state: [F; T] = core::array::from_fn(|_| F::rand(&mut rng));
+        let round_constants: Vec<F> = (0..T).map(|_| F::rand(&mut rng)).collect();
+        let v = MonolithPermuteVar::<F, T>::default();
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        let mut state_var = state
+            .iter()
+            .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect::<Vec<_>>();
+        v.concrete(&mut state_var).unwrap();
+        F::mds_multiply(&mut state);
+        assert_eq!(state_var.value().unwrap(), state);
+        v.concrete_wrc(&mut state_var, &round_constants).unwrap();
+        F::mds_multiply_with_rc(&mut state, &round_constants);
+        assert_eq!(state_var.value().unwrap(), state);
+        assert_eq!(cs.num_constraints(), 0);
+        assert_eq!(cs.num_witness_variables(), T);
+        assert!(cs.is_satisfied().unwrap());
+
+        let mut state_var = state.map(FpVar::Constant).to_vec();
+        v.concrete_wrc(&mut state_var, &round_constants).unwrap();
+        F::mds_multiply_with_rc(&mut state, &round_constants);
+        assert!(state_var.is_constant());
+        assert_eq!(state_var.value().unwrap(), state);
+
+        assert_eq!(
+            v.concrete(&mut state_var[1..]),
+            Err(SynthesisError::Unsatisfiable)
+        );
+    }
+
+    #[test]
+    pub fn concrete_is_linear() {
+        check_concrete::<FP64, 8>();
+        check_concrete::<FP64, 12>();
+        check_concrete::<F31, 16>();
+    }
+
+    #[test]
+    pub fn permute_constraint_count() {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<FP64>::new_ref();
+        let mut state = (0..12)
+            .map(|_| FpVar::new_witness(cs.clone(), || Ok(FP64::rand(&mut rng))).unwrap())
+            .collect::<Vec<_>>();
+        let params = CRH64::<12>::setup(&mut rng).unwrap();
+        MonolithPermuteVar::<FP64, 12>::default()
+            .permute(&mut state, &params)
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        // 6 rounds of 4 bars at 260 constraints each and 11 squares in the bricks; the MDS
+        // layers are linear.
+        assert_eq!(cs.num_constraints(), 6 * (4 * 260 + 11));
+    }
 }
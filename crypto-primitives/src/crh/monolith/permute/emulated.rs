@@ -0,0 +1,264 @@
+//! Monolith-64 in a constraint system over another field than Goldilocks, for instance to verify
+//! hashes of a Goldilocks STARK inside a proof over a pairing-friendly curve. Goldilocks elements
+//! are [`EmulatedFpVar`]s, split into limbs of the constraint field.
+
+use crate::crh::monolith::fields::goldilocks::Fr as F64;
+use crate::crh::monolith::fields::MonolithField;
+use crate::crh::monolith::permute::constraints::s_box_var;
+use crate::crh::monolith::permute::MonolithPermute;
+use crate::crh::monolith::MonolithParams;
+use ark_ff::{PrimeField, Zero};
+use ark_r1cs_std::fields::emulated_fp::params::{get_params, OptimizationType};
+use ark_r1cs_std::fields::emulated_fp::{AllocatedEmulatedFpVar, EmulatedFpVar};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSystemRef, OptimizationGoal, SynthesisError};
+use ark_std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+/// A Goldilocks element in a constraint system over `CF`.
+pub type F64Var<CF> = EmulatedFpVar<F64, CF>;
+
+/// R1CS gadget for [`MonolithPermute`] over Goldilocks with a state of `T` elements, in a
+/// constraint system over `CF`.
+///
+/// The bars work on the canonical bits of each element, which the emulated field gadget already
+/// range checks, and build their output limbs from the S-box bits without another range check.
+/// The MDS coefficients are small, so the MDS layers only double and add, which emulated
+/// elements mostly absorb without constraints.
+pub struct EmulatedMonolithPermuteVar<CF: PrimeField, const T: usize> {
+    /// The MDS matrix of width `T` as canonical integers.
+    mds: Vec<Vec<u64>>,
+    field_phantom: PhantomData<CF>,
+}
+
+impl<CF: PrimeField, const T: usize> Default for EmulatedMonolithPermuteVar<CF, T> {
+    fn default() -> Self {
+        let () = MonolithPermute::<F64, T>::WIDTH_CHECK;
+        let mds = F64::mds_matrix(T)
+            .iter()
+            .map(|row| row.iter().map(|m| m.into_bigint().0[0]).collect())
+            .collect();
+        Self {
+            mds,
+            field_phantom: PhantomData,
+        }
+    }
+}
+
+impl<CF: PrimeField, const T: usize> EmulatedMonolithPermuteVar<CF, T> {
+    /// The bar on a single element; see [`MonolithField::bar`]. Constants stay constant.
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub fn bar(&self, element: &F64Var<CF>) -> Result<F64Var<CF>, SynthesisError> {
+        let element = match element {
+            EmulatedFpVar::Constant(c) => return Ok(EmulatedFpVar::Constant(c.bar())),
+            EmulatedFpVar::Var(v) => v,
+        };
+        // The canonical bits, padded with zeros to whole limbs.
+        let bits = element.to_bits_le()?;
+        let mut out = Vec::with_capacity(64);
+        let mut offset = 0;
+        for size in F64::LIMB_SIZES {
+            let size = *size as usize;
+            out.extend(s_box_var(&bits[offset..offset + size])?);
+            offset += size;
+        }
+        Self::from_canonical_bits(element.cs(), &out)
+    }
+
+    /// The emulated element with the little-endian `bits`, which encode an integer below the
+    /// Goldilocks modulus. Its limbs are linear combinations of the bits, so this is free.
+    fn from_canonical_bits(
+        cs: ConstraintSystemRef<CF>,
+        bits: &[Boolean<CF>],
+    ) -> Result<F64Var<CF>, SynthesisError> {
+        // The same choice `AllocatedEmulatedFpVar` makes, so both operands of later arithmetic
+        // have the same limb sizes.
+        let optimization_type = match cs.optimization_goal() {
+            OptimizationGoal::Weight => OptimizationType::Weight,
+            OptimizationGoal::None | OptimizationGoal::Constraints => OptimizationType::Constraints,
+        };
+        let params = get_params(
+            F64::MODULUS_BIT_SIZE as usize,
+            CF::MODULUS_BIT_SIZE as usize,
+            optimization_type,
+        );
+        let mut limbs = bits
+            .chunks(params.bits_per_limb)
+            .map(Boolean::le_bits_to_fp)
+            .collect::<Result<Vec<_>, _>>()?;
+        limbs.resize(params.num_limbs, FpVar::zero());
+        // Emulated elements keep their most significant limb first.
+        limbs.reverse();
+        Ok(EmulatedFpVar::Var(AllocatedEmulatedFpVar {
+            cs,
+            limbs,
+            num_of_additions_over_normal_form: CF::zero(),
+            is_in_the_normal_form: true,
+            target_phantom: PhantomData,
+        }))
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub fn bars(
+        &self,
+        input: &mut [F64Var<CF>],
+        params: &MonolithParams<F64>,
+    ) -> Result<(), SynthesisError> {
+        for ele in input.iter_mut().take(params.bar_per_round().into()) {
+            *ele = self.bar(ele)?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub fn bricks(&self, input: &mut [F64Var<CF>]) -> Result<(), SynthesisError> {
+        for i in (1..input.len()).rev() {
+            let m = input[i - 1].square()?;
+            input[i] += m;
+        }
+        Ok(())
+    }
+
+    /// Multiplies `input` by the MDS matrix and adds `round_constants`, if there are any.
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub fn concrete(
+        &self,
+        input: &mut [F64Var<CF>],
+        round_constants: Option<&[F64]>,
+    ) -> Result<(), SynthesisError> {
+        if input.len() != T || round_constants.is_some_and(|rc| rc.len() != T) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut out = Vec::with_capacity(T);
+        for (i, row) in self.mds.iter().enumerate() {
+            let mut cur = F64Var::constant(round_constants.map_or(F64::zero(), |rc| rc[i]));
+            for (inp, m) in input.iter().zip(row) {
+                cur += Self::mul_by_small_constant(inp, *m)?;
+            }
+            out.push(cur);
+        }
+        input.clone_from_slice(&out);
+        Ok(())
+    }
+
+    /// `element * m` by double-and-add, which needs no multiplication of emulated elements.
+    fn mul_by_small_constant(element: &F64Var<CF>, m: u64) -> Result<F64Var<CF>, SynthesisError> {
+        let mut acc = F64Var::zero();
+        for i in (0..u64::BITS - m.leading_zeros()).rev() {
+            // Not `double_in_place`, whose default in `FieldVar` triples.
+            acc = acc.double()?;
+            if (m >> i) & 1 == 1 {
+                acc += element;
+            }
+        }
+        Ok(acc)
+    }
+
+    /// Runs the permutation on `input`, which holds exactly `T` elements. An input or parameters
+    /// for another width fail with [`SynthesisError::Unsatisfiable`] before any constraint is
+    /// added.
+    pub fn permute(
+        &self,
+        input: &mut [F64Var<CF>],
+        params: &MonolithParams<F64>,
+    ) -> Result<(), SynthesisError> {
+        if input.len() != T || params.state_size() != T {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        // The number of additions an emulated element has absorbed only depends on the circuit,
+        // and multiplications stop synthesizing once it grows too large. Starting every
+        // permutation from the normal form keeps it where the tests cover it, also when the
+        // input is the output of a previous permutation.
+        for ele in input.iter_mut() {
+            if let EmulatedFpVar::Var(v) = ele {
+                if !v.is_in_the_normal_form {
+                    let normal = F64Var::new_witness(v.cs(), || v.value())?;
+                    normal.enforce_equal(ele)?;
+                    *ele = normal;
+                }
+            }
+        }
+        self.concrete(input, None)?;
+        for rc in params.round_constants.iter() {
+            self.bars(input, params)?;
+            self.bricks(input)?;
+            self.concrete(input, Some(rc))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crh::monolith::CRH64;
+    use crate::crh::CRHScheme;
+    use crate::sponge::constraints::check_setup_mode;
+    use ark_ed_on_bls12_381::Fq as CF;
+    use ark_ff::{Field, One, UniformRand};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
+
+    fn check_permute<const T: usize>() {
+        let mut rng = test_rng();
+        let params = CRH64::<T>::setup(&mut rng).unwrap();
+        let v = EmulatedMonolithPermuteVar::<CF, T>::default();
+        let mut state: [F64; T] = core::array::from_fn(|_| F64::rand(&mut rng));
+        // Values at the top of the field, where a sloppy range check would go wrong.
+        state[0] = -F64::from(1u64);
+        state[1] = -F64::from(2u64);
+
+        let cs = ConstraintSystem::<CF>::new_ref();
+        let mut state_var = state
+            .iter()
+            .map(|x| F64Var::new_witness(cs.clone(), || Ok(*x)).unwrap())
+            .collect::<Vec<_>>();
+        let mut constant_var = state.map(F64Var::constant).to_vec();
+        MonolithPermute::<F64, T>::permute(&mut state, &params).unwrap();
+        v.permute(&mut state_var, &params).unwrap();
+        assert_eq!(state_var.value().unwrap(), state);
+        assert!(cs.is_satisfied().unwrap());
+
+        v.permute(&mut constant_var, &params).unwrap();
+        assert!(constant_var.is_constant());
+        assert_eq!(constant_var.value().unwrap(), state);
+
+        assert_eq!(
+            v.permute(&mut state_var[1..], &params),
+            Err(SynthesisError::Unsatisfiable)
+        );
+
+        // The same constraints come out in setup mode, without any witness values.
+        check_setup_mode(&state, |vars: &[F64Var<CF>]| {
+            let mut out = vars.to_vec();
+            v.permute(&mut out, &params)?;
+            Ok(out)
+        });
+    }
+
+    #[test]
+    fn permute_consistency() {
+        check_permute::<8>();
+        check_permute::<12>();
+    }
+
+    #[test]
+    fn bar_consistency() {
+        let mut rng = test_rng();
+        let v = EmulatedMonolithPermuteVar::<CF, 12>::default();
+        let mut values = vec![F64::zero(), F64::one(), -F64::one(), F64::from(u32::MAX)];
+        values.extend((0..10).map(|_| F64::rand(&mut rng)));
+        for value in values {
+            let cs = ConstraintSystem::<CF>::new_ref();
+            let ele = F64Var::new_witness(cs.clone(), || Ok(value)).unwrap();
+            let out = v.bar(&ele).unwrap();
+            assert_eq!(out.value().unwrap(), value.bar());
+            // The output takes part in arithmetic like any other emulated element.
+            let square = out.square().unwrap();
+            assert_eq!(square.value().unwrap(), value.bar().square());
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+}
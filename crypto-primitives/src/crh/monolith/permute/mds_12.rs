@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::crh::monolith::fields::goldilocks::reduce_u128;
 use ark_ff::BigInteger;
 use ark_ff::BigInteger64;
 // FFT-BASED MDS MULTIPLICATION HELPER FUNCTIONS
@@ -10,19 +11,40 @@ use ark_ff::BigInteger64;
 use ark_ff::Fp64;
 use ark_ff::FpConfig;
 use ark_ff::PrimeField;
-
-/// This module contains helper functions as well as constants used to perform a 12x12 vector-matrix
-/// multiplication. The special form of our MDS matrix i.e. being circulant, allows us to reduce
-/// the vector-matrix multiplication to a Hadamard product of two vectors in "frequency domain".
-/// This follows from the simple fact that every circulant matrix has the columns of the discrete
-/// Fourier transform matrix as orthogonal eigenvectors.
-/// The implementation also avoids the use of 3-point FFTs, and 3-point iFFTs, and substitutes that
-/// with explicit expressions. It also avoids, due to the form of our matrix in the frequency domain,
-/// divisions by 2 and repeated modular reductions. This is because of our explicit choice of
-/// an MDS matrix that has small powers of 2 entries in frequency domain.
-/// The following implementation has benefited greatly from the discussions and insights of
-/// Hamish Ivey-Law and Jacqueline Nabaglo of Polygon Zero.
-/// The circulant matrix is identified by its first row: [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8].
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+
+// This module contains helper functions as well as constants used to perform a 12x12 vector-matrix
+// multiplication. The special form of our MDS matrix i.e. being circulant, allows us to reduce
+// the vector-matrix multiplication to a Hadamard product of two vectors in "frequency domain".
+// This follows from the simple fact that every circulant matrix has the columns of the discrete
+// Fourier transform matrix as orthogonal eigenvectors.
+// The implementation also avoids the use of 3-point FFTs, and 3-point iFFTs, and substitutes that
+// with explicit expressions. It also avoids, due to the form of our matrix in the frequency domain,
+// divisions by 2 and repeated modular reductions. This is because of our explicit choice of
+// an MDS matrix that has small powers of 2 entries in frequency domain.
+// The following implementation has benefited greatly from the discussions and insights of
+// Hamish Ivey-Law and Jacqueline Nabaglo of Polygon Zero.
+// The circulant matrix is identified by its first row: [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8].
+
+/// First row of the circulant MDS matrix.
+pub const MDS_ROW: [u64; 12] = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
+
+/// First row of the inverse of the MDS matrix, which is circulant as well.
+pub const MDS_INVERSE_ROW: [u64; 12] = [
+    14868391535953158196,
+    13278298489594233127,
+    389999932707070822,
+    9782021734907796003,
+    4829905704463175582,
+    7567822018949214430,
+    14205019324568680367,
+    15489674211196160593,
+    17636013826542227504,
+    16254215311946436093,
+    3641486184877122796,
+    11069068059762973582,
+];
 
 // MDS matrix in frequency domain.
 // More precisely, this is the output of the three 4-point (real) FFTs of the first column of
@@ -83,7 +105,9 @@ pub fn mds_multiply_with_rc<T: FpConfig<1>>(
     }
 }
 
-pub fn mds_multiply_u128<T: FpConfig<1>>(state: &mut [u128; 12]) {
+/// Multiplies a state of integers below 2^65 by the MDS matrix and reduces every output to its
+/// canonical representative.
+pub fn mds_multiply_u128(state: &mut [u128; 12]) {
     // Using the linearity of the operations we can split the state into a low||high decomposition
     // and operate on each with no overflow and then combine/reduce the result to a field element.
     let mut state_l = [0u64; 12];
@@ -98,15 +122,9 @@ pub fn mds_multiply_u128<T: FpConfig<1>>(state: &mut [u128; 12]) {
     let state_h = mds_multiply_freq(state_h);
     let state_l = mds_multiply_freq(state_l);
     for r in 0..12 {
-        // Both have less than 40 bits
-        state[r] = state_l[r] as u128 + ((state_h[r] as u128) << 32);
-        state[r] = u64::from_le_bytes(
-            Fp64::<T>::from(state[r])
-                .into_bigint()
-                .to_bytes_le()
-                .try_into()
-                .unwrap(),
-        ) as u128;
+        // Less than 40 and 42 bits respectively, since the high halves have at most 33 bits.
+        let s = state_l[r] as u128 + ((state_h[r] as u128) << 32);
+        state[r] = reduce_u128(s) as u128;
     }
 }
 
@@ -126,10 +144,8 @@ pub fn circ_mat<T: FpConfig<1>>(row: &[u64]) -> Vec<Vec<Fp64<T>>> {
     mat
 }
 
-pub fn mds_multiply_with_rc_u128<T: FpConfig<1>>(
-    state: &mut [u128; 12],
-    round_constants: &[Fp64<T>; 12],
-) {
+/// Same as [`mds_multiply_u128`], adding the canonical `round_constants` before the reduction.
+pub fn mds_multiply_with_rc_u128(state: &mut [u128; 12], round_constants: &[u64; 12]) {
     // Using the linearity of the operations we can split the state into a low||high decomposition
     // and operate on each with no overflow and then combine/reduce the result to a field element.
     let mut state_l = [0u64; 12];
@@ -145,16 +161,9 @@ pub fn mds_multiply_with_rc_u128<T: FpConfig<1>>(
     let state_l = mds_multiply_freq(state_l);
 
     for r in 0..12 {
-        // Both have less than 40 bits
-        state[r] = state_l[r] as u128 + ((state_h[r] as u128) << 32);
-        state[r] += round_constants[r].0 .0[0] as u128;
-        state[r] = u64::from_le_bytes(
-            Fp64::<T>::from(state[r])
-                .into_bigint()
-                .to_bytes_le()
-                .try_into()
-                .unwrap(),
-        ) as u128;
+        // Less than 40 and 42 bits respectively, since the high halves have at most 33 bits.
+        let s = state_l[r] as u128 + ((state_h[r] as u128) << 32) + round_constants[r] as u128;
+        state[r] = reduce_u128(s) as u128;
     }
 }
 
@@ -294,13 +303,12 @@ pub fn ifft4_real_unreduced(y: (i64, (i64, i64), i64)) -> [u64; 4] {
 pub mod mds_tests {
     use super::*;
     use crate::crh::monolith::fields::goldilocks::Fr as F64;
-    use crate::crh::monolith::fields::goldilocks::FrConfig;
-    use ark_ff::fields::MontBackend;
+    use crate::crh::monolith::fields::goldilocks::MODULUS;
     use ark_ff::fields::PrimeField;
     use ark_ff::UniformRand;
     use ark_std::ops::{AddAssign, MulAssign};
+    use ark_std::test_rng;
     use ark_std::Zero;
-    use rand::thread_rng;
     static TESTRUNS: usize = 5;
     type Scalar = F64;
 
@@ -323,7 +331,7 @@ pub mod mds_tests {
         let row = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
         let mat = circ_mat(&row);
         let round_const = [Scalar::zero(); 12];
-        let mut rng = thread_rng();
+        let mut rng = test_rng();
         for _ in 0..TESTRUNS {
             let input: [Scalar; 12] = [
                 Scalar::rand(&mut rng),
@@ -352,8 +360,8 @@ pub mod mds_tests {
                     u64::from_le_bytes(src.into_bigint().to_bytes_le().try_into().unwrap()) as u128;
             }
             let mut output5 = output4.to_owned();
-            mds_multiply_with_rc_u128(&mut output4, &round_const);
-            mds_multiply_u128::<MontBackend<FrConfig, 1>>(&mut output5);
+            mds_multiply_with_rc_u128(&mut output4, &[0u64; 12]);
+            mds_multiply_u128(&mut output5);
             for (a, b) in output1.iter().zip(output4.iter()) {
                 assert_eq!(
                     u64::from_le_bytes(a.into_bigint().to_bytes_le().try_into().unwrap()),
@@ -366,6 +374,14 @@ pub mod mds_tests {
                     *b as u64
                 );
             }
+
+            // Unreduced inputs, as left by the bricks, reduce to the same output.
+            let mut output6 = [0u128; 12];
+            for (src, des) in input.iter().zip(output6.iter_mut()) {
+                *des = src.into_bigint().0[0] as u128 + MODULUS as u128;
+            }
+            mds_multiply_u128(&mut output6);
+            assert_eq!(output5, output6);
         }
     }
 }
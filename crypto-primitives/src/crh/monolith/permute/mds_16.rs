@@ -0,0 +1,98 @@
+use ark_ff::Fp64;
+use ark_ff::FpConfig;
+use ark_ff::PrimeField;
+
+/// This module contains the 16x16 vector-matrix multiplication used by Monolith-31.
+/// The matrix is circulant and is identified by its first row:
+/// [61402, 17845, 26798, 59689, 12021, 40901, 41351, 27521, 56951, 12034, 53865, 43244, 7454,
+///  33823, 28750, 1108].
+/// All entries fit in 16 bits and the state elements in 31 bits, so a full row can be accumulated
+/// in a `u64` (less than 2^51) and reduced only once.
+pub const MDS_ROW: [u64; 16] = [
+    61402, 17845, 26798, 59689, 12021, 40901, 41351, 27521, 56951, 12034, 53865, 43244, 7454,
+    33823, 28750, 1108,
+];
+
+/// First row of the inverse of the MDS matrix, which is circulant as well.
+pub const MDS_INVERSE_ROW: [u64; 16] = [
+    1325460196, 123350314, 1124192176, 122549823, 16225590, 1769133998, 1596704794, 1757886559,
+    1616238592, 214937165, 1824003953, 2109236387, 383889434, 1415320228, 1467424601, 877575281,
+];
+
+pub fn mds_multiply<T: FpConfig<1>>(state: &mut [Fp64<T>; 16]) {
+    let mut state_u64 = [0u64; 16];
+    for r in 0..16 {
+        state_u64[r] = state[r].into_bigint().0[0];
+    }
+
+    let state_u64 = mds_multiply_unreduced(state_u64);
+    for r in 0..16 {
+        state[r] = Fp64::<T>::from(state_u64[r]);
+    }
+}
+
+pub fn mds_multiply_with_rc<T: FpConfig<1>>(
+    state: &mut [Fp64<T>; 16],
+    round_constants: &[Fp64<T>; 16],
+) {
+    let mut state_u64 = [0u64; 16];
+    for r in 0..16 {
+        state_u64[r] = state[r].into_bigint().0[0];
+    }
+
+    let state_u64 = mds_multiply_unreduced(state_u64);
+    for r in 0..16 {
+        state[r] = Fp64::<T>::from(state_u64[r]);
+        state[r] += round_constants[r];
+    }
+}
+
+// Computes the (unreduced) product of the circulant matrix with a state of elements below 2^31.
+#[inline(always)]
+pub(crate) fn mds_multiply_unreduced(state: [u64; 16]) -> [u64; 16] {
+    let mut out = [0u64; 16];
+    for (r, out) in out.iter_mut().enumerate() {
+        for (c, s) in state.iter().enumerate() {
+            *out += MDS_ROW[(16 + c - r) % 16] * s;
+        }
+    }
+    out
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// test
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod mds_tests {
+    use super::*;
+    use crate::crh::monolith::fields::mersenne::Fr as F31;
+    use crate::crh::monolith::permute::mds_12::circ_mat;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_std::Zero;
+    static TESTRUNS: usize = 5;
+
+    #[test]
+    fn kats() {
+        let mat = circ_mat(&MDS_ROW);
+        let round_const = [F31::zero(); 16];
+        let mut rng = test_rng();
+        for _ in 0..TESTRUNS {
+            let input: [F31; 16] = core::array::from_fn(|_| F31::rand(&mut rng));
+
+            let mut output1 = [F31::zero(); 16];
+            for (row, out) in output1.iter_mut().enumerate() {
+                for (col, inp) in input.iter().enumerate() {
+                    *out += mat[row][col] * inp;
+                }
+            }
+            let mut output2 = input.to_owned();
+            mds_multiply_with_rc(&mut output2, &round_const);
+            assert_eq!(output1, output2);
+            let mut output3 = input.to_owned();
+            mds_multiply(&mut output3);
+            assert_eq!(output1, output3);
+        }
+    }
+}
@@ -1,75 +1,200 @@
-use std::usize;
-
-use crate::crh::monolith::fields::goldilocks::{Fr as F64, FrConfig};
+use crate::crh::monolith::fields::goldilocks::{
+    bar_u64, reduce_u128, reduce_u128_ct, Fr as F64, EPSILON,
+};
+use crate::crh::monolith::fields::{is_supported_width, MonolithField};
 use crate::crh::monolith::MonolithParams;
-use ark_ff::BigInteger;
-use ark_ff::Field;
-use ark_ff::MontBackend;
+use crate::sponge::Permutation;
+use crate::Error;
 use ark_ff::PrimeField;
-use ark_ff::Zero;
+use ark_std::cfg_chunks_mut;
+use ark_std::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use ark_std::vec::Vec;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-mod mds_12;
-mod mds_8;
+pub(crate) mod mds_12;
+pub(crate) mod mds_16;
+pub(crate) mod mds_8;
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
+#[cfg(feature = "r1cs")]
+pub mod emulated;
 
-pub struct MonolithPermute<const T: usize>;
-impl<const T: usize> MonolithPermute<T> {
-    pub fn s(byt: u8) -> u8 {
-        (byt ^ (!byt.rotate_left(1) & byt.rotate_left(2) & byt.rotate_left(3))).rotate_left(1)
-    }
-    pub fn bar(element: F64) -> F64 {
-        // let mut le_bytes = element.into_bigint().to_bytes_le();
-        // for byt in &mut le_bytes {
-        //     *byt = Self::s(*byt);
-        // }
-        let mut ele: u64 =
-            u64::from_le_bytes(element.into_bigint().to_bytes_le().try_into().unwrap());
-
-        let limbl1 = ((ele & 0x8080808080808080) >> 7) | ((ele & 0x7F7F7F7F7F7F7F7F) << 1); //left rot by 1
-        let limbl2 = ((ele & 0xC0C0C0C0C0C0C0C0) >> 6) | ((ele & 0x3F3F3F3F3F3F3F3F) << 2); //left rot by 2
-        let limbl3 = ((ele & 0xE0E0E0E0E0E0E0E0) >> 5) | ((ele & 0x1F1F1F1F1F1F1F1F) << 3); //left rot by 3
-        ele = ele ^ (!limbl1 & limbl2 & limbl3);
-
-        ele = ((ele & 0x8080808080808080) >> 7) | ((ele & 0x7F7F7F7F7F7F7F7F) << 1); //left rot by 1
-        <F64 as PrimeField>::from_le_bytes_mod_order(&ele.to_le_bytes())
-        // le_bytes.iter_mut().for_each(|byt| *byt = Self::s(*byt));
-        // <F64 as PrimeField>::from_le_bytes_mod_order(&le_bytes)
-    }
-    pub fn bars(input: &mut [F64; T], params: &MonolithParams) {
-        let mut out_bars: Vec<_> = vec![];
-        for (ind, ele) in input.iter().enumerate().into_iter() {
-            if ind >= params.bar_per_round.into() {
-                out_bars.push(*ele);
-                continue;
-            }
-            out_bars.push(Self::bar(*ele));
+/// Number of states `permute_batch` interleaves, one per lane of its structure-of-arrays state.
+pub const BATCH_LANES: usize = 8;
+
+/// Number of groups of `BATCH_LANES` states `permute_batch` hands to one task.
+const BATCH_GROUPS_PER_TASK: usize = 64;
+
+/// The Monolith permutation over the field `F` with a state of `T` elements.
+///
+/// `T` has to be one of `F::WIDTHS`; any other width is rejected when the permutation is
+/// instantiated:
+///
+/// ```compile_fail
+/// use ark_crypto_primitives::crh::monolith::fields::goldilocks::Fr;
+/// use ark_crypto_primitives::crh::monolith::permute::MonolithPermute;
+///
+/// let mut state = [Fr::from(0u64); 10];
+/// MonolithPermute::<Fr, 10>::concrete(&mut state);
+/// ```
+pub struct MonolithPermute<F: MonolithField, const T: usize> {
+    field_phantom: PhantomData<F>,
+}
+impl<F: MonolithField, const T: usize> MonolithPermute<F, T> {
+    /// Fails const evaluation, and with it the build, if `T` is not a width supported by `F`.
+    pub(crate) const WIDTH_CHECK: () = assert!(
+        is_supported_width(F::WIDTHS, T),
+        "unsupported Monolith state size for this field"
+    );
+
+    pub fn bar(element: F) -> F {
+        element.bar()
+    }
+    pub fn bars(input: &mut [F; T], params: &MonolithParams<F>) {
+        for ele in input.iter_mut().take(params.bar_per_round.into()) {
+            *ele = Self::bar(*ele);
         }
-        input.copy_from_slice(&out_bars[..]);
     }
-    pub fn bricks(input: &mut [F64; T]) {
+    pub fn bricks(input: &mut [F; T]) {
         for i in (1..input.len()).rev() {
             input[i] += input[i - 1].square();
         }
     }
-    pub fn concrete_wrc(input: &mut [F64; T], round_constant: &[F64]) {
-        if T == 8 {
-            mds_8::mds_multiply_with_rc(
-                input.as_mut().try_into().unwrap(),
-                round_constant.try_into().unwrap(),
-            );
-        } else if T == 12 {
-            mds_12::mds_multiply_with_rc(
-                input.as_mut().try_into().unwrap(),
-                round_constant.try_into().unwrap(),
-            );
+    pub fn concrete_wrc(input: &mut [F; T], round_constant: &[F]) {
+        let () = Self::WIDTH_CHECK;
+        F::mds_multiply_with_rc(input, round_constant);
+    }
+    pub fn concrete(input: &mut [F; T]) {
+        let () = Self::WIDTH_CHECK;
+        F::mds_multiply(input);
+    }
+    /// Applies the permutation to `input` in place.
+    ///
+    /// Fails if `input` does not hold exactly `T` elements or if `params` were generated for a
+    /// different state size.
+    pub fn permute(input: &mut [F], params: &MonolithParams<F>) -> Result<(), Error> {
+        params.check_state_size(T)?;
+        if input.len() != T {
+            return Err(Error::IncorrectInputLength(input.len()));
+        }
+        let mut inp: [F; T] = [F::zero(); T];
+        inp.copy_from_slice(input);
+        F::permute_state(&mut inp, params);
+        input.copy_from_slice(&inp);
+        Ok(())
+    }
+    /// Applies the inverse of the permutation to `input` in place, so that
+    /// `permute_inverse(permute(x)) == x`.
+    ///
+    /// The rounds are undone in reverse order with field arithmetic: subtract the round
+    /// constants and multiply by the inverse MDS matrix, undo the bricks from the first element
+    /// on, and apply the inverse S-box to every limb of the barred elements. The inverse MDS
+    /// matrix comes from the static table of [`MonolithField::mds_inverse_row`]. Fails under the
+    /// same conditions as [`MonolithPermute::permute`].
+    pub fn permute_inverse(input: &mut [F], params: &MonolithParams<F>) -> Result<(), Error> {
+        params.check_state_size(T)?;
+        if input.len() != T {
+            return Err(Error::IncorrectInputLength(input.len()));
+        }
+        let mut inp: [F; T] = [F::zero(); T];
+        inp.copy_from_slice(input);
+        let mds_inverse = F::mds_inverse_row(T);
+        for rc in params.round_constants.iter().rev() {
+            Self::concrete_inverse(&mut inp, mds_inverse, rc);
+            Self::bricks_inverse(&mut inp);
+            Self::bars_inverse(&mut inp, params);
+        }
+        Self::concrete_inverse(&mut inp, mds_inverse, &[F::zero(); T]);
+        input.copy_from_slice(&inp);
+        Ok(())
+    }
+    pub fn bars_inverse(input: &mut [F; T], params: &MonolithParams<F>) {
+        for ele in input.iter_mut().take(params.bar_per_round.into()) {
+            *ele = ele.bar_inverse();
+        }
+    }
+    pub fn bricks_inverse(input: &mut [F; T]) {
+        // x[i - 1] has already been restored when x[i] is.
+        for i in 1..input.len() {
+            input[i] -= input[i - 1].square();
         }
     }
+    /// Subtracts `round_constant` and multiplies by the circulant matrix with first row
+    /// `mds_inverse`, the inverse of the concrete layer with these constants.
+    pub fn concrete_inverse(input: &mut [F; T], mds_inverse: &[F], round_constant: &[F]) {
+        let mut shifted = *input;
+        for (x, c) in shifted.iter_mut().zip(round_constant) {
+            *x -= c;
+        }
+        for (r, out) in input.iter_mut().enumerate() {
+            *out = (0..T)
+                .map(|c| mds_inverse[(c + T - r) % T] * shifted[c])
+                .sum();
+        }
+    }
+    /// Evaluates the permutation round by round with field arithmetic. This is what `permute`
+    /// does unless the field provides a faster representation; it is kept as a reference for
+    /// the specialised paths.
+    pub fn permute_reference(input: &mut [F; T], params: &MonolithParams<F>) {
+        Self::concrete(input);
 
-    pub fn concrete_wrc_u128(input: &mut [u128; T], round_constant: &[F64]) {
-        if T == 8 {
-            mds_8::mds_multiply_with_rc_u128::<MontBackend<FrConfig, 1>>(
+        for rc in params.round_constants.iter() {
+            Self::bars(input, params);
+            Self::bricks(input);
+            Self::concrete_wrc(input, rc);
+        }
+    }
+}
+
+impl<F: MonolithField, const T: usize> Permutation<F> for MonolithPermute<F, T> {
+    type Parameters = MonolithParams<F>;
+
+    fn width(_parameters: &MonolithParams<F>) -> usize {
+        T
+    }
+
+    fn permute(state: &mut [F], parameters: &MonolithParams<F>) -> Result<(), Error> {
+        MonolithPermute::<F, T>::permute(state, parameters)
+    }
+}
+
+/// The widths Monolith-64 has an MDS layer for.
+enum Mds64 {
+    Width8,
+    Width12,
+}
+
+/// Monolith-64 on raw integers. The state holds canonical representatives as `u128`s, except
+/// between the bricks and the following concrete layer, where the sums are left unreduced (below
+/// 2^65). The concrete layer reduces every element once, so no field element is built until the
+/// permutation is done.
+impl<const T: usize> MonolithPermute<F64, T> {
+    pub fn bars_u128(input: &mut [u128; T], params: &MonolithParams<F64>) {
+        for ele in input.iter_mut().take(params.bar_per_round.into()) {
+            *ele = reduce_u128(bar_u64(*ele as u64) as u128) as u128;
+        }
+    }
+    pub fn bricks_u128(input: &mut [u128; T]) {
+        // Every x[i - 1] is canonical here, so its square fits in a u128.
+        for i in (1..input.len()).rev() {
+            input[i] += reduce_u128(input[i - 1] * input[i - 1]) as u128;
+        }
+    }
+    /// The MDS layer of width `T`. Evaluating it for a width without one fails the build, so a
+    /// width added to `F64::WIDTHS` cannot fall through the dispatch below.
+    const MDS: Mds64 = match T {
+        8 => Mds64::Width8,
+        12 => Mds64::Width12,
+        _ => panic!("no Monolith-64 MDS layer for this state size"),
+    };
+
+    pub fn concrete_wrc_u128(input: &mut [u128; T], round_constant: &[u64]) {
+        let () = Self::WIDTH_CHECK;
+        match Self::MDS {
+            Mds64::Width8 => mds_8::mds_multiply_with_rc_u128(
                 input
                     .as_mut()
                     .try_into()
@@ -77,9 +202,8 @@ impl<const T: usize> MonolithPermute<T> {
                 round_constant
                     .try_into()
                     .expect("incorrect input size of round constants"),
-            );
-        } else if T == 12 {
-            mds_12::mds_multiply_with_rc_u128::<MontBackend<FrConfig, 1>>(
+            ),
+            Mds64::Width12 => mds_12::mds_multiply_with_rc_u128(
                 input
                     .as_mut()
                     .try_into()
@@ -87,43 +211,362 @@ impl<const T: usize> MonolithPermute<T> {
                 round_constant
                     .try_into()
                     .expect("incorrect input size of round constants"),
-            );
-        }
-    }
-    pub fn concrete(input: &mut [F64; T]) {
-        if T == 8 {
-            mds_8::mds_multiply(input.as_mut().try_into().unwrap());
-        } else if T == 12 {
-            mds_12::mds_multiply(input.as_mut().try_into().unwrap());
+            ),
         }
     }
     pub fn concrete_u128(input: &mut [u128; T]) {
-        if T == 8 {
-            mds_8::mds_multiply_u128::<MontBackend<FrConfig, 1>>(
+        let () = Self::WIDTH_CHECK;
+        match Self::MDS {
+            Mds64::Width8 => mds_8::mds_multiply_u128(
                 input
                     .as_mut()
                     .try_into()
                     .expect("incorrect input size for mds"),
-            );
-        } else if T == 12 {
-            mds_12::mds_multiply_u128::<MontBackend<FrConfig, 1>>(
+            ),
+            Mds64::Width12 => mds_12::mds_multiply_u128(
                 input
                     .as_mut()
                     .try_into()
                     .expect("incorrect input size for mds"),
-            );
+            ),
         }
     }
-    pub fn permute(input: &mut [F64], params: &MonolithParams) {
-        let mut inp: [F64; T] = [F64::zero(); T];
-        inp.copy_from_slice(input);
-        Self::concrete(&mut inp);
+    /// Permutes a state of canonical representatives in place. The caller is responsible for
+    /// `params` matching the width, as checked by [`MonolithPermute::permute`].
+    pub fn permute_u128(input: &mut [u128; T], params: &MonolithParams<F64>) {
+        Self::concrete_u128(input);
 
+        let mut round_constant = [0u64; T];
         for rc in params.round_constants.iter() {
-            Self::bars(&mut inp, params);
-            Self::bricks(&mut inp);
-            Self::concrete_wrc(&mut inp, rc);
+            for (raw, c) in round_constant.iter_mut().zip(rc) {
+                *raw = c.into_bigint().0[0];
+            }
+            Self::bars_u128(input, params);
+            Self::bricks_u128(input);
+            Self::concrete_wrc_u128(input, &round_constant);
         }
-        input.copy_from_slice(&inp);
+    }
+
+    /// Applies the permutation to a state of raw integers in constant time.
+    ///
+    /// This is the evaluation path for keyed uses of Monolith-64, such as a PRF or a MAC with
+    /// the key in the state. Its running time and memory accesses depend on `T` and `params`,
+    /// which are public, but not on the state: the bars are bit operations on the packed limbs,
+    /// the bricks and the concrete layer are integer multiplications and additions, and every
+    /// reduction selects its result with masks the compiler cannot turn into branches. The
+    /// elements of `input` do not have to be canonical; they are reduced first, and the output
+    /// is canonical.
+    ///
+    /// The rest of the Monolith code makes no such promise and should not see secrets:
+    /// - [`MonolithPermute::permute`] computes the same function, but its reductions are left
+    ///   to the optimiser, which compiles some of them into branches;
+    /// - converting between `u64` and [`F64`] goes through the Montgomery arithmetic of
+    ///   `ark-ff`, which ends with a conditional subtraction, so keep secret values as `u64`;
+    /// - the sponge, [`MonolithBytes`](crate::crh::monolith::MonolithBytes), Monolith-31 and
+    ///   the gadgets use field arithmetic and these conversions;
+    /// - parameter generation rejection-samples round constants, which only leaks information
+    ///   about the public SHAKE128 seed.
+    ///
+    /// Fails if `params` were generated for a different state size.
+    pub fn permute_ct(input: &mut [u64; T], params: &MonolithParams<F64>) -> Result<(), Error> {
+        params.check_state_size(T)?;
+        let bars = usize::from(params.bar_per_round).min(T);
+        for ele in input.iter_mut() {
+            *ele = reduce_u128_ct(*ele as u128);
+        }
+
+        Self::concrete_ct(input, &[0; T]);
+        let mut round_constant = [0u64; T];
+        for rc in params.round_constants.iter() {
+            for (raw, c) in round_constant.iter_mut().zip(rc) {
+                *raw = c.into_bigint().0[0];
+            }
+            for ele in input.iter_mut().take(bars) {
+                *ele = reduce_u128_ct(bar_u64(*ele) as u128);
+            }
+            for i in (1..T).rev() {
+                let square = reduce_u128_ct(input[i - 1] as u128 * input[i - 1] as u128);
+                input[i] = reduce_u128_ct(input[i] as u128 + square as u128);
+            }
+            Self::concrete_ct(input, &round_constant);
+        }
+        Ok(())
+    }
+
+    /// The concrete layer of [`MonolithPermute::permute_ct`] on canonical elements. The
+    /// FFT-based multiplications are plain integer arithmetic; only the reductions differ from
+    /// [`MonolithPermute::concrete_wrc_u128`].
+    fn concrete_ct(state: &mut [u64; T], round_constant: &[u64; T]) {
+        let () = Self::WIDTH_CHECK;
+        let mut state_l = [0u64; T];
+        let mut state_h = [0u64; T];
+        for ((l, h), s) in state_l.iter_mut().zip(state_h.iter_mut()).zip(state.iter()) {
+            *h = s >> 32;
+            *l = (*s as u32) as u64;
+        }
+        let freq = |s: [u64; T]| -> [u64; T] {
+            match Self::MDS {
+                Mds64::Width8 => mds_8::mds_multiply_freq(s[..].try_into().unwrap())[..]
+                    .try_into()
+                    .unwrap(),
+                Mds64::Width12 => mds_12::mds_multiply_freq(s[..].try_into().unwrap())[..]
+                    .try_into()
+                    .unwrap(),
+            }
+        };
+        let (state_l, state_h) = (freq(state_l), freq(state_h));
+        for (i, s) in state.iter_mut().enumerate() {
+            let sum = state_l[i] as u128 + ((state_h[i] as u128) << 32) + round_constant[i] as u128;
+            *s = reduce_u128_ct(sum);
+        }
+    }
+
+    /// Permutes every state in `states` in place.
+    ///
+    /// The states are processed `BATCH_LANES` at a time with element `i` of all of them stored
+    /// next to each other, so the bars and bricks become branch-free loops over independent lanes
+    /// that the compiler can vectorise. The round constants are converted once for the whole
+    /// batch, and with the `parallel` feature runs of `BATCH_LANES * 64` states are spread over
+    /// threads.
+    pub fn permute_batch(
+        states: &mut [[F64; T]],
+        params: &MonolithParams<F64>,
+    ) -> Result<(), Error> {
+        params.check_state_size(T)?;
+        let round_constants: Vec<[u64; T]> = params
+            .round_constants
+            .iter()
+            .map(|rc| core::array::from_fn(|i| rc[i].into_bigint().0[0]))
+            .collect();
+        let bars = usize::from(params.bar_per_round).min(T);
+
+        // With the `parallel` feature every task takes a run of groups, so that scheduling costs
+        // little next to the permutations.
+        cfg_chunks_mut!(states, BATCH_LANES * BATCH_GROUPS_PER_TASK).for_each(|run| {
+            for chunk in run.chunks_mut(BATCH_LANES) {
+                Self::permute_lanes(chunk, &round_constants, bars);
+            }
+        });
+        Ok(())
+    }
+
+    fn permute_lanes(states: &mut [[F64; T]], round_constants: &[[u64; T]], bars: usize) {
+        // Unused lanes of the last group are permuted as zero states and then dropped.
+        let mut lanes = [[0u64; BATCH_LANES]; T];
+        for (lane, state) in states.iter().enumerate() {
+            for (i, ele) in state.iter().enumerate() {
+                lanes[i][lane] = ele.into_bigint().0[0];
+            }
+        }
+
+        Self::concrete_lanes(&mut lanes, &[0; T]);
+        for rc in round_constants {
+            for ele in lanes.iter_mut().take(bars).flatten() {
+                *ele = reduce_u128(bar_u64(*ele) as u128);
+            }
+            for i in (1..T).rev() {
+                let (previous, current) = lanes.split_at_mut(i);
+                for (x, y) in current[0].iter_mut().zip(previous[i - 1].iter()) {
+                    let square = reduce_u128(*y as u128 * *y as u128);
+                    // Branch-free addition; the sum stays below 2^64 but may not be canonical,
+                    // which the concrete layer does not need.
+                    let (sum, carry) = x.overflowing_add(square);
+                    *x = sum.wrapping_add(EPSILON * carry as u64);
+                }
+            }
+            Self::concrete_lanes(&mut lanes, rc);
+        }
+
+        for (lane, state) in states.iter_mut().enumerate() {
+            for (i, ele) in state.iter_mut().enumerate() {
+                *ele = F64::from(lanes[i][lane]);
+            }
+        }
+    }
+
+    /// Applies the concrete layer to `BATCH_LANES` interleaved states and adds `round_constant`.
+    ///
+    /// Row `r` of the circulant MDS matrix is the first row rotated right by `r`, so every
+    /// output element is a sum of whole input lane arrays, each scaled by one coefficient of
+    /// that row. The coefficients are below 2^5 and the lanes below 2^64, so the sums are
+    /// accumulated in `u128` and reduced once per element.
+    pub fn concrete_lanes(lanes: &mut [[u64; BATCH_LANES]; T], round_constant: &[u64; T]) {
+        let () = Self::WIDTH_CHECK;
+        let row: [u64; T] = match Self::MDS {
+            Mds64::Width8 => core::array::from_fn(|i| mds_8::MDS_ROW[i]),
+            Mds64::Width12 => core::array::from_fn(|i| mds_12::MDS_ROW[i]),
+        };
+        let input = *lanes;
+        for (r, (output, rc)) in lanes.iter_mut().zip(round_constant.iter()).enumerate() {
+            let mut sums = [*rc as u128; BATCH_LANES];
+            for (c, lane) in input.iter().enumerate() {
+                let coefficient = row[(c + T - r) % T] as u128;
+                for (sum, x) in sums.iter_mut().zip(lane.iter()) {
+                    *sum += coefficient * *x as u128;
+                }
+            }
+            for (o, sum) in output.iter_mut().zip(sums.iter()) {
+                *o = reduce_u128(*sum);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crh::monolith::fields::goldilocks::MODULUS;
+    use crate::crh::{monolith::CRH64, CRHScheme};
+    use ark_std::rand::Rng;
+    use ark_std::test_rng;
+
+    #[test]
+    fn reduce_u128_ct_edge_cases() {
+        let p = MODULUS as u128;
+        let mut rng = test_rng();
+        let mut values = vec![
+            0,
+            1,
+            p - 1,
+            p,
+            p + 1,
+            u64::MAX as u128,
+            1 << 64,
+            (1 << 96) - 1,
+            1 << 96,
+            (p - 1) * (p - 1),
+            u128::MAX,
+        ];
+        values.extend((0..1000).map(|_| rng.gen::<u128>()));
+        values.extend((0..1000).map(|_| rng.gen::<u64>() as u128));
+        for x in values {
+            assert_eq!(reduce_u128_ct(x) as u128, x % p, "reducing {x}");
+            assert_eq!(reduce_u128_ct(x), reduce_u128(x));
+        }
+    }
+
+    fn check_permute_ct<const T: usize>() {
+        let mut rng = test_rng();
+        let params = CRH64::<T>::setup(&mut rng).unwrap();
+        for _ in 0..20 {
+            let raw: [u64; T] = core::array::from_fn(|_| rng.gen());
+            let mut expected = raw.map(F64::from);
+            MonolithPermute::<F64, T>::permute(&mut expected, &params).unwrap();
+
+            let mut state = raw;
+            MonolithPermute::<F64, T>::permute_ct(&mut state, &params).unwrap();
+            assert_eq!(state.map(F64::from), expected);
+            assert!(state.iter().all(|x| *x < MODULUS));
+        }
+
+        // Non-canonical inputs are the same elements as their reductions.
+        let mut state = [MODULUS; T];
+        let mut zero = [0u64; T];
+        MonolithPermute::<F64, T>::permute_ct(&mut state, &params).unwrap();
+        MonolithPermute::<F64, T>::permute_ct(&mut zero, &params).unwrap();
+        assert_eq!(state, zero);
+    }
+
+    #[test]
+    fn permute_ct_consistency() {
+        check_permute_ct::<8>();
+        check_permute_ct::<12>();
+
+        let params = CRH64::<8>::setup(&mut test_rng()).unwrap();
+        assert!(MonolithPermute::<F64, 12>::permute_ct(&mut [0; 12], &params).is_err());
+    }
+
+    fn check_concrete_lanes<const T: usize>() {
+        let mut rng = test_rng();
+        let mut lanes: [[u64; BATCH_LANES]; T] = core::array::from_fn(|_| rng.gen());
+        // The bricks leave elements below 2^64 but not necessarily canonical.
+        lanes[0][0] = u64::MAX;
+        let round_constant: [u64; T] = core::array::from_fn(|_| rng.gen::<u64>() % MODULUS);
+        let mut expected = lanes;
+        for lane in 0..BATCH_LANES {
+            let mut state: [u128; T] = core::array::from_fn(|i| expected[i][lane] as u128);
+            MonolithPermute::<F64, T>::concrete_wrc_u128(&mut state, &round_constant);
+            for (output, s) in expected.iter_mut().zip(state.iter()) {
+                output[lane] = *s as u64;
+            }
+        }
+        MonolithPermute::<F64, T>::concrete_lanes(&mut lanes, &round_constant);
+        assert_eq!(lanes, expected);
+    }
+
+    #[test]
+    fn concrete_lanes_matches_concrete() {
+        check_concrete_lanes::<8>();
+        check_concrete_lanes::<12>();
+    }
+
+    /// Welch's t-statistic of two samples of timings.
+    #[cfg(feature = "std")]
+    fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+        let stats = |x: &[f64]| {
+            let n = x.len() as f64;
+            let mean = x.iter().sum::<f64>() / n;
+            let var = x.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0);
+            (mean, var / n)
+        };
+        let ((mean_a, var_a), (mean_b, var_b)) = (stats(a), stats(b));
+        (mean_a - mean_b) / (var_a + var_b).sqrt()
+    }
+
+    /// A dudect-style leakage test of [`MonolithPermute::permute_ct`]: time the permutation on a
+    /// fixed state and on random states, interleaved at random, and compare the two timing
+    /// distributions with Welch's t-test. A statistic above 10 in absolute value is evidence of
+    /// a timing difference. Timings are noisy under a test harness, so run it on a quiet
+    /// machine with `cargo test --release permute_ct_timing -- --ignored`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore]
+    fn permute_ct_timing() {
+        use std::time::Instant;
+
+        const SAMPLES: usize = 200_000;
+        const REPETITIONS: usize = 8;
+        let mut rng = test_rng();
+        let params = CRH64::<12>::setup(&mut rng).unwrap();
+        let fixed = [0u64; 12];
+
+        // All inputs are drawn up front so that only the permutations are timed.
+        let inputs: Vec<(bool, [u64; 12])> = (0..SAMPLES)
+            .map(|_| {
+                let class: bool = rng.gen();
+                let input = if class {
+                    core::array::from_fn(|_| rng.gen())
+                } else {
+                    fixed
+                };
+                (class, input)
+            })
+            .collect();
+        let timings: Vec<(bool, f64)> = inputs
+            .iter()
+            .map(|(class, input)| {
+                let start = Instant::now();
+                for _ in 0..REPETITIONS {
+                    let mut state = core::hint::black_box(*input);
+                    MonolithPermute::<F64, 12>::permute_ct(&mut state, &params).unwrap();
+                    core::hint::black_box(state);
+                }
+                (*class, start.elapsed().as_nanos() as f64)
+            })
+            .collect();
+
+        // Drop the slowest tenth, which is mostly preemption and interrupts.
+        let mut sorted: Vec<f64> = timings.iter().map(|(_, t)| *t).collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let cutoff = sorted[SAMPLES * 9 / 10];
+        let class_timings = |class: bool| -> Vec<f64> {
+            timings
+                .iter()
+                .filter(|(c, t)| *c == class && *t <= cutoff)
+                .map(|(_, t)| *t)
+                .collect()
+        };
+        let t = welch_t(&class_timings(false), &class_timings(true));
+        assert!(t.abs() < 10.0, "timing depends on the state: t = {t:.2}");
     }
 }
@@ -46,6 +46,8 @@ pub mod sponge;
 #[derive(Debug)]
 pub enum Error {
     IncorrectInputLength(usize),
+    IncorrectStateSize(usize),
+    InsecureParameters(&'static str),
     NotPrimeOrder,
     GenericError(Box<dyn ark_std::error::Error + Send>),
     SerializationError(ark_serialize::SerializationError),
@@ -55,6 +57,8 @@ impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::IncorrectInputLength(len) => write!(f, "incorrect input length: {len}"),
+            Self::IncorrectStateSize(size) => write!(f, "incorrect state size: {size}"),
+            Self::InsecureParameters(reason) => write!(f, "insecure parameters: {reason}"),
             Self::NotPrimeOrder => write!(f, "element is not prime order"),
             Self::GenericError(e) => write!(f, "{e}"),
             Self::SerializationError(e) => write!(f, "{e}"),
@@ -4,7 +4,7 @@ use ark_ec::{
     short_weierstrass::SWCurveConfig as SWModelParameters,
     twisted_edwards::TECurveConfig as TEModelParameters,
 };
-use ark_ff::models::{Fp, FpConfig};
+use ark_ff::models::{Fp, FpConfig, QuadExtConfig, QuadExtField};
 use ark_ff::{BigInteger, Field, PrimeField, ToConstraintField};
 use ark_serialize::CanonicalSerialize;
 #[cfg(not(feature = "std"))]
@@ -167,6 +167,20 @@ impl<P: FpConfig<N>, const N: usize> Absorb for Fp<P, N> {
     }
 }
 
+/// An element `c0 + c1 * X` of a quadratic extension is absorbed as `c0` followed by `c1`.
+impl<P: QuadExtConfig> Absorb for QuadExtField<P>
+where
+    P::BaseField: Absorb,
+{
+    fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
+        self.c0.to_sponge_bytes(dest);
+        self.c1.to_sponge_bytes(dest);
+    }
+    fn to_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
+        P::BaseField::batch_to_sponge_field_elements(&[self.c0, self.c1], dest)
+    }
+}
+
 macro_rules! impl_absorbable_unsigned {
     ($t:ident) => {
         impl Absorb for $t {
@@ -495,4 +509,24 @@ mod tests {
 
         assert_ne!(dest1, dest2);
     }
+
+    #[cfg(feature = "crh")]
+    #[test]
+    fn test_quadratic_extension_absorb_order() {
+        use crate::crh::monolith::fields::goldilocks::{Fr as F64, Fr2};
+
+        let mut rng = test_rng();
+        let element = Fr2::rand(&mut rng);
+
+        let mut elements: Vec<F64> = Vec::new();
+        element.to_sponge_field_elements(&mut elements);
+        assert_eq!(elements, vec![element.c0, element.c1]);
+
+        let mut bytes = Vec::new();
+        element.to_sponge_bytes(&mut bytes);
+        let mut expected = Vec::new();
+        element.c0.to_sponge_bytes(&mut expected);
+        element.c1.to_sponge_bytes(&mut expected);
+        assert_eq!(bytes, expected);
+    }
 }
@@ -6,6 +6,7 @@ use ark_ff::{Field, PrimeField};
 use ark_r1cs_std::boolean::Boolean;
 use ark_r1cs_std::convert::{ToBytesGadget, ToConstraintFieldGadget};
 use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::quadratic_extension::{QuadExtVar, QuadExtVarConfig};
 use ark_r1cs_std::fields::{FieldOpsBounds, FieldVar};
 use ark_r1cs_std::groups::curves::short_weierstrass::{
     AffineVar as SWAffineVar, ProjectiveVar as SWProjectiveVar,
@@ -164,6 +165,24 @@ where
     }
 }
 
+/// Absorbs `c0` followed by `c1`, like the native `QuadExtField`.
+impl<BF, P> AbsorbGadget<P::BasePrimeField> for QuadExtVar<BF, P>
+where
+    BF: FieldVar<P::BaseField, P::BasePrimeField> + AbsorbGadget<P::BasePrimeField>,
+    P: QuadExtVarConfig<BF>,
+    for<'a> &'a BF: FieldOpsBounds<'a, P::BaseField, BF>,
+{
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<P::BasePrimeField>>, SynthesisError> {
+        let mut bytes = self.c0.to_sponge_bytes()?;
+        bytes.extend(self.c1.to_sponge_bytes()?);
+        Ok(bytes)
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<P::BasePrimeField>>, SynthesisError> {
+        BF::batch_to_sponge_field_elements(&[self.c0.clone(), self.c1.clone()])
+    }
+}
+
 impl<F: PrimeField, A: AbsorbGadget<F>> AbsorbGadget<F> for &[A] {
     fn to_sponge_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
         A::batch_to_sponge_bytes(self)
@@ -15,6 +15,9 @@ use ark_std::vec::Vec;
 mod absorb;
 pub use absorb::*;
 
+mod permutation;
+pub use permutation::*;
+
 /// Converts little-endian bits to a list of emulated elements.
 pub fn bits_le_to_emulated<'a, F: PrimeField, CF: PrimeField>(
     cs: ConstraintSystemRef<CF>,
@@ -67,10 +70,9 @@ pub fn bits_le_to_emulated<'a, F: PrimeField, CF: PrimeField>(
 
         let mut limbs = Vec::new();
         for k in 0..params.num_limbs {
-            let gadget =
-                AllocatedFp::new_witness(ark_relations::ns!(cs, "alloc"), || Ok(val[k])).unwrap();
+            let gadget = AllocatedFp::new_witness(ark_relations::ns!(cs, "alloc"), || Ok(val[k]))?;
             lc[k] = lc[k].clone() - (CF::one(), gadget.variable);
-            cs.enforce_constraint(lc!(), lc!(), lc[k].clone()).unwrap();
+            cs.enforce_constraint(lc!(), lc!(), lc[k].clone())?;
             limbs.push(FpVar::<CF>::from(gadget));
         }
 
@@ -0,0 +1,112 @@
+use crate::sponge::Permutation;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+
+/// Circuit counterpart of a [`Permutation`], with the parameters as constants.
+pub trait PermutationGadget<F: PrimeField> {
+    /// The permutation this gadget computes.
+    type Native: Permutation<F>;
+
+    /// Permutes `state` in place. Fails with [`SynthesisError::Unsatisfiable`] if `state` does not
+    /// hold as many elements as the native permutation with `parameters` acts on.
+    fn permute(
+        state: &mut [FpVar<F>],
+        parameters: &<Self::Native as Permutation<F>>::Parameters,
+    ) -> Result<(), SynthesisError>;
+}
+
+/// Checks that the gadget `G` computes its native permutation with `parameters`: on random
+/// witness states the outputs agree and the constraints are satisfied, on constant states the
+/// gadget adds no constraints, in setup mode it synthesizes the same constraints without any
+/// witness values, and states of the wrong width are rejected by both.
+#[cfg(test)]
+pub(crate) fn check_permutation_consistency<F, G>(
+    parameters: &<G::Native as Permutation<F>>::Parameters,
+) where
+    F: PrimeField,
+    G: PermutationGadget<F>,
+{
+    use ark_r1cs_std::{alloc::AllocVar, fields::FieldVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, vec::Vec};
+
+    let mut rng = test_rng();
+    let width = G::Native::width(parameters);
+    for _ in 0..3 {
+        let mut state: Vec<F> = (0..width).map(|_| F::rand(&mut rng)).collect();
+        let input = state.clone();
+        G::Native::permute(&mut state, parameters).unwrap();
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        let mut state_var = Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(input.clone())).unwrap();
+        G::permute(&mut state_var, parameters).unwrap();
+        assert_eq!(state_var.value().unwrap(), state);
+        assert!(cs.is_satisfied().unwrap());
+
+        check_setup_mode(&input, |vars: &[FpVar<F>]| {
+            let mut state = vars.to_vec();
+            G::permute(&mut state, parameters)?;
+            Ok(state)
+        });
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        let mut state_const = Vec::<FpVar<F>>::new_constant(cs.clone(), input).unwrap();
+        G::permute(&mut state_const, parameters).unwrap();
+        assert_eq!(state_const.value().unwrap(), state);
+        assert!(state_const.iter().all(|x| x.is_constant()));
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    for len in [width - 1, width + 1] {
+        let mut state = vec![F::zero(); len];
+        assert!(G::Native::permute(&mut state, parameters).is_err());
+        let mut state_var = vec![FpVar::<F>::zero(); len];
+        assert!(matches!(
+            G::permute(&mut state_var, parameters),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
+
+/// Synthesizes `circuit` on witnesses for `inputs`, then again in setup mode on witnesses
+/// without values, and checks that both runs succeed with the same constraints and that the
+/// outputs have no values in setup mode. `circuit` returns the variables of its output.
+#[cfg(test)]
+pub(crate) fn check_setup_mode<V, CF, Val, O>(
+    inputs: &[Val],
+    circuit: impl Fn(&[V]) -> Result<ark_std::vec::Vec<O>, SynthesisError>,
+) where
+    V: ark_r1cs_std::alloc::AllocVar<Val, CF>,
+    CF: PrimeField,
+    Val: Clone,
+    O: ark_r1cs_std::R1CSVar<CF>,
+{
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
+    use ark_std::vec::Vec;
+
+    let cs = ConstraintSystem::<CF>::new_ref();
+    let vars = inputs
+        .iter()
+        .map(|x| V::new_witness(cs.clone(), || Ok(x.clone())))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let outputs = circuit(&vars).unwrap();
+    assert!(outputs.iter().all(|o| o.value().is_ok()));
+    assert!(cs.is_satisfied().unwrap());
+
+    let setup = ConstraintSystem::<CF>::new_ref();
+    setup.set_mode(SynthesisMode::Setup);
+    let vars = inputs
+        .iter()
+        .map(|_| {
+            V::new_witness(setup.clone(), || {
+                Err::<Val, _>(SynthesisError::AssignmentMissing)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let outputs = circuit(&vars).unwrap();
+    assert!(outputs.iter().any(|o| o.value().is_err()));
+    assert_eq!(setup.num_constraints(), cs.num_constraints());
+}
@@ -1,10 +1,11 @@
-use crate::crh::monolith::fields::goldilocks::Fr as FP64;
+use crate::crh::monolith::fields::MonolithField;
 use crate::crh::monolith::permute::constraints::MonolithPermuteVar;
 use crate::sponge::constraints::AbsorbGadget;
 use crate::sponge::constraints::{CryptographicSpongeVar, SpongeWithGadget};
 use crate::sponge::DuplexSpongeMode;
-use ark_ff::PrimeField;
+use ark_ff::QuadExtConfig;
 use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::quadratic_extension::{QuadExtVar, QuadExtVarConfig};
 use ark_r1cs_std::prelude::*;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 #[cfg(not(feature = "std"))]
@@ -13,43 +14,53 @@ use ark_std::vec::Vec;
 use super::generic_sponge::{MonolithSponge, SpongeConfig};
 
 #[derive(Clone)]
-/// the gadget for Poseidon sponge
+/// the gadget for Monolith sponge
 ///
-/// This implementation of Poseidon is entirely from Fractal's implementation in [COS20][cos]
-/// with small syntax changes.
+/// The duplex construction is the same as the one of the Poseidon sponge gadget, which is taken
+/// from Fractal's implementation in [COS20][cos].
 ///
 /// [cos]: https://eprint.iacr.org/2019/1076
-pub struct MonolithSpongeVar {
+pub struct MonolithSpongeVar<F: MonolithField, const T: usize> {
     /// Constraint system
-    pub cs: ConstraintSystemRef<FP64>,
+    pub cs: ConstraintSystemRef<F>,
 
     /// Sponge Parameters
-    pub parameters: SpongeConfig,
+    pub parameters: SpongeConfig<F, T>,
+    /// Round constants as circuit variables, one row of `T` for each row of the parameters'
+    /// round constants. If set, the permutation adds these instead of the constants in
+    /// `parameters`.
+    pub round_constants: Option<Vec<Vec<FpVar<F>>>>,
 
     // Sponge State
     /// The sponge's state
-    pub state: Vec<FpVar<FP64>>,
+    pub state: Vec<FpVar<F>>,
     /// The mode
     pub mode: DuplexSpongeMode,
 }
 
-impl SpongeWithGadget<FP64> for MonolithSponge {
-    type Var = MonolithSpongeVar;
+impl<F: MonolithField, const T: usize> SpongeWithGadget<F> for MonolithSponge<F, T> {
+    type Var = MonolithSpongeVar<F, T>;
 }
 
-impl MonolithSpongeVar {
+impl<F: MonolithField, const T: usize> MonolithSpongeVar<F, T> {
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn permute(&mut self) -> Result<(), SynthesisError> {
-        let mono_permute_var = MonolithPermuteVar::<12>;
-        mono_permute_var.permute(&mut self.state, &self.parameters.params)?;
-        Ok(())
+        let mono_permute_var = MonolithPermuteVar::<F, T>::default();
+        match &self.round_constants {
+            Some(round_constants) => mono_permute_var.permute_with_round_constants(
+                &mut self.state,
+                &self.parameters.params,
+                round_constants,
+            ),
+            None => mono_permute_var.permute(&mut self.state, &self.parameters.params),
+        }
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn absorb_internal(
         &mut self,
         mut rate_start_index: usize,
-        elements: &[FpVar<FP64>],
+        elements: &[FpVar<F>],
     ) -> Result<(), SynthesisError> {
         let mut remaining_elements = elements;
         loop {
@@ -85,7 +96,7 @@ impl MonolithSpongeVar {
     fn squeeze_internal(
         &mut self,
         mut rate_start_index: usize,
-        output: &mut [FpVar<FP64>],
+        output: &mut [FpVar<F>],
     ) -> Result<(), SynthesisError> {
         let mut remaining_output = output;
         loop {
@@ -118,13 +129,34 @@ impl MonolithSpongeVar {
     }
 }
 
-impl CryptographicSpongeVar<FP64, MonolithSponge> for MonolithSpongeVar {
-    type Parameters = SpongeConfig;
+impl<F: MonolithField, const T: usize> MonolithSpongeVar<F, T> {
+    /// Squeezes `num_elements` elements of a quadratic extension of `F`, matching
+    /// [`MonolithSponge::squeeze_quadratic_extension_elements`].
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    pub fn squeeze_quadratic_extension_elements<P>(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<QuadExtVar<FpVar<F>, P>>, SynthesisError>
+    where
+        P: QuadExtConfig<BaseField = F, BasePrimeField = F> + QuadExtVarConfig<FpVar<F>>,
+    {
+        let elements = self.squeeze_field_elements(2 * num_elements)?;
+        Ok(elements
+            .chunks_exact(2)
+            .map(|c| QuadExtVar::new(c[0].clone(), c[1].clone()))
+            .collect())
+    }
+}
+
+impl<F: MonolithField, const T: usize> CryptographicSpongeVar<F, MonolithSponge<F, T>>
+    for MonolithSpongeVar<F, T>
+{
+    type Parameters = SpongeConfig<F, T>;
 
     #[tracing::instrument(target = "r1cs", skip(cs))]
-    fn new(cs: ConstraintSystemRef<FP64>, parameters: &SpongeConfig) -> Self {
-        let zero = FpVar::<FP64>::zero();
-        let state = vec![zero; parameters.rate + parameters.capacity];
+    fn new(cs: ConstraintSystemRef<F>, parameters: &SpongeConfig<F, T>) -> Self {
+        let zero = FpVar::<F>::zero();
+        let state = vec![zero; T];
         let mode = DuplexSpongeMode::Absorbing {
             next_absorb_index: 0,
         };
@@ -132,18 +164,19 @@ impl CryptographicSpongeVar<FP64, MonolithSponge> for MonolithSpongeVar {
         Self {
             cs,
             parameters: parameters.clone(),
+            round_constants: None,
             state,
             mode,
         }
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
-    fn cs(&self) -> ConstraintSystemRef<FP64> {
+    fn cs(&self) -> ConstraintSystemRef<F> {
         self.cs.clone()
     }
 
     #[tracing::instrument(target = "r1cs", skip(self, input))]
-    fn absorb(&mut self, input: &impl AbsorbGadget<FP64>) -> Result<(), SynthesisError> {
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
         let input = input.to_sponge_field_elements()?;
         if input.is_empty() {
             return Ok(());
@@ -170,13 +203,13 @@ impl CryptographicSpongeVar<FP64, MonolithSponge> for MonolithSpongeVar {
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
-    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<FP64>>, SynthesisError> {
-        let usable_bytes = ((FP64::MODULUS_BIT_SIZE - 1) / 8) as usize;
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let usable_bytes = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
 
         let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
         let src_elements = self.squeeze_field_elements(num_elements)?;
 
-        let mut bytes: Vec<UInt8<FP64>> = Vec::with_capacity(usable_bytes * num_elements);
+        let mut bytes: Vec<UInt8<F>> = Vec::with_capacity(usable_bytes * num_elements);
         for elem in &src_elements {
             bytes.extend_from_slice(&elem.to_bytes_le()?[..usable_bytes]);
         }
@@ -186,13 +219,13 @@ impl CryptographicSpongeVar<FP64, MonolithSponge> for MonolithSpongeVar {
     }
 
     #[tracing::instrument(target = "r1cs", skip(self))]
-    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<FP64>>, SynthesisError> {
-        let usable_bits = (FP64::MODULUS_BIT_SIZE - 1) as usize;
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let usable_bits = (F::MODULUS_BIT_SIZE - 1) as usize;
 
         let num_elements = (num_bits + usable_bits - 1) / usable_bits;
         let src_elements = self.squeeze_field_elements(num_elements)?;
 
-        let mut bits: Vec<Boolean<FP64>> = Vec::with_capacity(usable_bits * num_elements);
+        let mut bits: Vec<Boolean<F>> = Vec::with_capacity(usable_bits * num_elements);
         for elem in &src_elements {
             bits.extend_from_slice(&elem.to_bits_le()?[..usable_bits]);
         }
@@ -205,7 +238,7 @@ impl CryptographicSpongeVar<FP64, MonolithSponge> for MonolithSpongeVar {
     fn squeeze_field_elements(
         &mut self,
         num_elements: usize,
-    ) -> Result<Vec<FpVar<FP64>>, SynthesisError> {
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
         let zero = FpVar::zero();
         let mut squeezed_elems = vec![zero; num_elements];
         match self.mode {
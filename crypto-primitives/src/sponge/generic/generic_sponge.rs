@@ -1,48 +1,54 @@
-use crate::crh::monolith::fields::goldilocks::Fr as F64;
+use crate::Error;
 use crate::{
-    crh::monolith::{permute::MonolithPermute, MonolithParams},
+    crh::monolith::{fields::MonolithField, permute::MonolithPermute, MonolithParams},
     sponge::{
         field_cast, squeeze_field_elements_with_sizes_default_impl, Absorb, CryptographicSponge,
         DuplexSpongeMode, FieldBasedCryptographicSponge, FieldElementSize, SpongeExt,
     },
 };
-use ark_ff::fields::Field;
-use ark_ff::{BigInteger, PrimeField};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::any::TypeId;
+use ark_ff::{BigInteger, PrimeField, QuadExtConfig, QuadExtField};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 #[cfg(not(feature = "std"))]
 use ark_std::vec::Vec;
-use ark_std::Zero;
+use ark_std::{any::TypeId, io::Read};
 
-/// Config and RNG used
-#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
-pub struct SpongeConfig {
+/// Config of a Monolith sponge with a state of `T` elements.
+///
+/// Built with [`SpongeConfig::new`], which checks that the rate and capacity add up to `T` and
+/// that the permutation parameters are for that state size, so a sponge built from it never
+/// has to check again.
+#[derive(Clone, Debug, CanonicalSerialize)]
+pub struct SpongeConfig<F: MonolithField, const T: usize> {
     /// The rate (in terms of number of field elements).
     /// See [On the Indifferentiability of the Sponge Construction](https://iacr.org/archive/eurocrypt2008/49650180/49650180.pdf)
     /// for more details on the rate and capacity of a sponge.
-    pub rate: usize,
+    pub(crate) rate: usize,
     /// The capacity (in terms of number of field elements).
-    pub capacity: usize,
-    pub params: MonolithParams,
+    pub(crate) capacity: usize,
+    pub(crate) params: MonolithParams<F>,
 }
 
+/// A duplex sponge over the Monolith permutation with a state of `T` elements of `F`.
 #[derive(Clone)]
-pub struct MonolithSponge {
+pub struct MonolithSponge<F: MonolithField, const T: usize> {
     /// Sponge Config
-    pub parameters: SpongeConfig,
+    pub parameters: SpongeConfig<F, T>,
     // Sponge State
     /// Current sponge's state (current elements in the permutation block)
-    pub state: Vec<F64>,
+    pub state: [F; T],
     /// Current mode (whether its absorbing or squeezing)
     pub mode: DuplexSpongeMode,
 }
 
-impl MonolithSponge {
+impl<F: MonolithField, const T: usize> MonolithSponge<F, T> {
     fn permute(&mut self) {
-        MonolithPermute::<12>::permute(self.state.as_mut_slice(), &self.parameters.params);
+        let () = MonolithPermute::<F, T>::WIDTH_CHECK;
+        F::permute_state(&mut self.state, &self.parameters.params);
     }
     // Absorbs everything in elements, this does not end in an absorbtion.
-    fn absorb_internal(&mut self, mut rate_start_index: usize, elements: &[F64]) {
+    fn absorb_internal(&mut self, mut rate_start_index: usize, elements: &[F]) {
         let mut remaining_elements = elements;
 
         loop {
@@ -74,7 +80,7 @@ impl MonolithSponge {
     }
 
     // Squeeze |output| many elements. This does not end in a squeeze
-    fn squeeze_internal(&mut self, mut rate_start_index: usize, output: &mut [F64]) {
+    fn squeeze_internal(&mut self, mut rate_start_index: usize, output: &mut [F]) {
         let mut output_remaining = output;
         loop {
             // if we can finish in this call
@@ -106,21 +112,87 @@ impl MonolithSponge {
     }
 }
 
-impl SpongeConfig {
-    /// Initialize the parameter for Poseidon Sponge.
-    pub fn new(rate: usize, capacity: usize, params: &MonolithParams) -> Self {
-        Self {
+impl<F: MonolithField, const T: usize> MonolithSponge<F, T> {
+    /// Squeezes `num_elements` elements of a quadratic extension of `F`. Each one is built from
+    /// two consecutive native elements, `c0` first, the same order they are absorbed in.
+    pub fn squeeze_quadratic_extension_elements<P: QuadExtConfig<BaseField = F>>(
+        &mut self,
+        num_elements: usize,
+    ) -> Vec<QuadExtField<P>> {
+        self.squeeze_native_field_elements(2 * num_elements)
+            .chunks_exact(2)
+            .map(|c| QuadExtField::new(c[0], c[1]))
+            .collect()
+    }
+}
+
+impl<F: MonolithField, const T: usize> SpongeConfig<F, T> {
+    /// Initialize the parameter for Monolith Sponge.
+    ///
+    /// Fails if `rate + capacity` is not `T`, if the rate is zero, or if `params` were generated
+    /// for a different state size.
+    pub fn new(rate: usize, capacity: usize, params: &MonolithParams<F>) -> Result<Self, Error> {
+        if rate == 0 || rate.checked_add(capacity) != Some(T) {
+            return Err(Error::IncorrectStateSize(rate.saturating_add(capacity)));
+        }
+        params.check_state_size(T)?;
+        Ok(Self {
             rate,
             capacity,
             params: params.clone(),
+        })
+    }
+
+    /// The rate (in terms of number of field elements).
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// The capacity (in terms of number of field elements).
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The parameters of the underlying permutation.
+    pub fn params(&self) -> &MonolithParams<F> {
+        &self.params
+    }
+}
+
+impl<F: MonolithField, const T: usize> Valid for SpongeConfig<F, T> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.params.check()?;
+        Self::new(self.rate, self.capacity, &self.params)
+            .map(|_| ())
+            .map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+impl<F: MonolithField, const T: usize> CanonicalDeserialize for SpongeConfig<F, T> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let rate = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let capacity = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let params = MonolithParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let config = Self {
+            rate,
+            capacity,
+            params,
+        };
+        if let Validate::Yes = validate {
+            config.check()?;
         }
+        Ok(config)
     }
 }
 
-impl CryptographicSponge for MonolithSponge {
-    type Config = SpongeConfig;
+impl<F: MonolithField, const T: usize> CryptographicSponge for MonolithSponge<F, T> {
+    type Config = SpongeConfig<F, T>;
     fn new(parameters: &Self::Config) -> Self {
-        let state = vec![F64::zero(); parameters.rate + parameters.capacity];
+        let state = [F::zero(); T];
         let mode = DuplexSpongeMode::Absorbing {
             next_absorb_index: 0,
         };
@@ -133,7 +205,7 @@ impl CryptographicSponge for MonolithSponge {
     }
 
     fn absorb(&mut self, input: &impl Absorb) {
-        let elems = input.to_sponge_field_elements_as_vec::<F64>();
+        let elems = input.to_sponge_field_elements_as_vec::<F>();
         if elems.is_empty() {
             return;
         }
@@ -157,7 +229,7 @@ impl CryptographicSponge for MonolithSponge {
     }
 
     fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
-        let usable_bytes = ((F64::MODULUS_BIT_SIZE - 1) / 8) as usize;
+        let usable_bytes = ((F::MODULUS_BIT_SIZE - 1) / 8) as usize;
 
         let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
         let src_elements = self.squeeze_native_field_elements(num_elements);
@@ -173,7 +245,7 @@ impl CryptographicSponge for MonolithSponge {
     }
 
     fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
-        let usable_bits = (F64::MODULUS_BIT_SIZE - 1) as usize;
+        let usable_bits = (F::MODULUS_BIT_SIZE - 1) as usize;
 
         let num_elements = (num_bits + usable_bits - 1) / usable_bits;
         let src_elements = self.squeeze_native_field_elements(num_elements);
@@ -192,7 +264,7 @@ impl CryptographicSponge for MonolithSponge {
         &mut self,
         sizes: &[FieldElementSize],
     ) -> Vec<F2> {
-        if F64::characteristic() == F2::characteristic() {
+        if F::characteristic() == F2::characteristic() {
             // native case
             let mut buf = Vec::with_capacity(sizes.len());
             field_cast(
@@ -207,7 +279,7 @@ impl CryptographicSponge for MonolithSponge {
     }
 
     fn squeeze_field_elements<F2: PrimeField>(&mut self, num_elements: usize) -> Vec<F2> {
-        if TypeId::of::<F64>() == TypeId::of::<F2>() {
+        if TypeId::of::<F>() == TypeId::of::<F2>() {
             let result = self.squeeze_native_field_elements(num_elements);
             let mut cast = Vec::with_capacity(result.len());
             field_cast(&result, &mut cast).unwrap();
@@ -220,9 +292,9 @@ impl CryptographicSponge for MonolithSponge {
     }
 }
 
-impl FieldBasedCryptographicSponge<F64> for MonolithSponge {
-    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F64> {
-        let mut squeezed_elems = vec![F64::zero(); num_elements];
+impl<F: MonolithField, const T: usize> FieldBasedCryptographicSponge<F> for MonolithSponge<F, T> {
+    fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        let mut squeezed_elems = vec![F::zero(); num_elements];
         match self.mode {
             DuplexSpongeMode::Absorbing {
                 next_absorb_index: _,
@@ -245,14 +317,14 @@ impl FieldBasedCryptographicSponge<F64> for MonolithSponge {
 }
 
 #[derive(Clone)]
-/// Stores the state of a Poseidon Sponge. Does not store any parameter.
-pub struct MonolithSpongeState {
-    state: Vec<F64>,
+/// Stores the state of a Monolith Sponge. Does not store any parameter.
+pub struct MonolithSpongeState<F: MonolithField, const T: usize> {
+    state: [F; T],
     mode: DuplexSpongeMode,
 }
 
-impl SpongeExt for MonolithSponge {
-    type State = MonolithSpongeState;
+impl<F: MonolithField, const T: usize> SpongeExt for MonolithSponge<F, T> {
+    type State = MonolithSpongeState<F, T>;
 
     fn from_state(state: Self::State, params: &Self::Config) -> Self {
         let mut sponge = Self::new(params);
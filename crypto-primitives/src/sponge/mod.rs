@@ -9,7 +9,11 @@ pub mod constraints;
 mod absorb;
 pub use absorb::*;
 
+mod permutation;
+pub use permutation::*;
+
 pub mod generic;
+
 /// The sponge for Poseidon
 ///
 /// This implementation of Poseidon is entirely from Fractal's implementation in [COS20][cos]
@@ -0,0 +1,20 @@
+use crate::Error;
+use ark_ff::PrimeField;
+
+/// A public permutation of a fixed number of elements of `F`, the building block of a sponge.
+///
+/// Implemented by a type standing for the permutation; the instance (round constants, MDS
+/// matrix, width) is selected by its parameters. [`PermutationGadget`] is the circuit
+/// counterpart.
+///
+/// [`PermutationGadget`]: crate::sponge::constraints::PermutationGadget
+pub trait Permutation<F: PrimeField> {
+    /// Everything that selects an instance of the permutation.
+    type Parameters: Clone;
+
+    /// Number of elements the permutation acts on with `parameters`.
+    fn width(parameters: &Self::Parameters) -> usize;
+
+    /// Permutes `state` in place. Fails if `state` does not hold `width(parameters)` elements.
+    fn permute(state: &mut [F], parameters: &Self::Parameters) -> Result<(), Error>;
+}
@@ -1,7 +1,7 @@
 use crate::sponge::constraints::AbsorbGadget;
-use crate::sponge::constraints::{CryptographicSpongeVar, SpongeWithGadget};
+use crate::sponge::constraints::{CryptographicSpongeVar, PermutationGadget, SpongeWithGadget};
 use crate::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
-use crate::sponge::DuplexSpongeMode;
+use crate::sponge::{DuplexSpongeMode, Permutation};
 
 use ark_ff::PrimeField;
 use ark_r1cs_std::fields::fp::FpVar;
@@ -36,42 +36,49 @@ impl<F: PrimeField> SpongeWithGadget<F> for PoseidonSponge<F> {
 }
 
 impl<F: PrimeField> PoseidonSpongeVar<F> {
-    #[tracing::instrument(target = "r1cs", skip(self))]
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
     fn apply_s_box(
-        &self,
+        parameters: &PoseidonConfig<F>,
         state: &mut [FpVar<F>],
         is_full_round: bool,
     ) -> Result<(), SynthesisError> {
         // Full rounds apply the S Box (x^alpha) to every element of state
         if is_full_round {
             for state_item in state.iter_mut() {
-                *state_item = state_item.pow_by_constant(&[self.parameters.alpha])?;
+                *state_item = state_item.pow_by_constant(&[parameters.alpha])?;
             }
         }
         // Partial rounds apply the S Box (x^alpha) to just the first element of state
         else {
-            state[0] = state[0].pow_by_constant(&[self.parameters.alpha])?;
+            state[0] = state[0].pow_by_constant(&[parameters.alpha])?;
         }
 
         Ok(())
     }
 
-    #[tracing::instrument(target = "r1cs", skip(self))]
-    fn apply_ark(&self, state: &mut [FpVar<F>], round_number: usize) -> Result<(), SynthesisError> {
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn apply_ark(
+        parameters: &PoseidonConfig<F>,
+        state: &mut [FpVar<F>],
+        round_number: usize,
+    ) -> Result<(), SynthesisError> {
         for (i, state_elem) in state.iter_mut().enumerate() {
-            *state_elem += self.parameters.ark[round_number][i];
+            *state_elem += parameters.ark[round_number][i];
         }
         Ok(())
     }
 
-    #[tracing::instrument(target = "r1cs", skip(self))]
-    fn apply_mds(&self, state: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn apply_mds(
+        parameters: &PoseidonConfig<F>,
+        state: &mut [FpVar<F>],
+    ) -> Result<(), SynthesisError> {
         let mut new_state = Vec::new();
         let zero = FpVar::<F>::zero();
         for i in 0..state.len() {
             let mut cur = zero.clone();
             for (j, state_elem) in state.iter().enumerate() {
-                let term = state_elem * self.parameters.mds[i][j];
+                let term = state_elem * parameters.mds[i][j];
                 cur += &term;
             }
             new_state.push(cur);
@@ -80,33 +87,39 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
         Ok(())
     }
 
-    #[tracing::instrument(target = "r1cs", skip(self))]
-    fn permute(&mut self) -> Result<(), SynthesisError> {
-        let full_rounds_over_2 = self.parameters.full_rounds / 2;
-        let mut state = self.state.clone();
+    /// Circuit counterpart of the Poseidon permutation with `parameters`.
+    #[tracing::instrument(target = "r1cs", skip(parameters))]
+    fn permute_state(
+        parameters: &PoseidonConfig<F>,
+        state: &mut [FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let full_rounds_over_2 = parameters.full_rounds / 2;
         for i in 0..full_rounds_over_2 {
-            self.apply_ark(&mut state, i)?;
-            self.apply_s_box(&mut state, true)?;
-            self.apply_mds(&mut state)?;
+            Self::apply_ark(parameters, state, i)?;
+            Self::apply_s_box(parameters, state, true)?;
+            Self::apply_mds(parameters, state)?;
         }
-        for i in full_rounds_over_2..(full_rounds_over_2 + self.parameters.partial_rounds) {
-            self.apply_ark(&mut state, i)?;
-            self.apply_s_box(&mut state, false)?;
-            self.apply_mds(&mut state)?;
+        for i in full_rounds_over_2..(full_rounds_over_2 + parameters.partial_rounds) {
+            Self::apply_ark(parameters, state, i)?;
+            Self::apply_s_box(parameters, state, false)?;
+            Self::apply_mds(parameters, state)?;
         }
 
-        for i in (full_rounds_over_2 + self.parameters.partial_rounds)
-            ..(self.parameters.partial_rounds + self.parameters.full_rounds)
+        for i in (full_rounds_over_2 + parameters.partial_rounds)
+            ..(parameters.partial_rounds + parameters.full_rounds)
         {
-            self.apply_ark(&mut state, i)?;
-            self.apply_s_box(&mut state, true)?;
-            self.apply_mds(&mut state)?;
+            Self::apply_ark(parameters, state, i)?;
+            Self::apply_s_box(parameters, state, true)?;
+            Self::apply_mds(parameters, state)?;
         }
-
-        self.state = state;
         Ok(())
     }
 
+    #[tracing::instrument(target = "r1cs", skip(self))]
+    fn permute(&mut self) -> Result<(), SynthesisError> {
+        Self::permute_state(&self.parameters, &mut self.state)
+    }
+
     #[tracing::instrument(target = "r1cs", skip(self))]
     fn absorb_internal(
         &mut self,
@@ -180,6 +193,20 @@ impl<F: PrimeField> PoseidonSpongeVar<F> {
     }
 }
 
+impl<F: PrimeField> PermutationGadget<F> for PoseidonSpongeVar<F> {
+    type Native = PoseidonSponge<F>;
+
+    fn permute(
+        state: &mut [FpVar<F>],
+        parameters: &PoseidonConfig<F>,
+    ) -> Result<(), SynthesisError> {
+        if state.len() != PoseidonSponge::width(parameters) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        Self::permute_state(parameters, state)
+    }
+}
+
 impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpongeVar<F> {
     type Parameters = PoseidonConfig<F>;
 
@@ -381,4 +408,11 @@ mod tests {
         let bits = &bits[0];
         assert_eq!(bits.len() as u32, Fr::MODULUS_BIT_SIZE - 1);
     }
+
+    #[test]
+    fn permutation_consistency() {
+        use crate::sponge::constraints::check_permutation_consistency;
+
+        check_permutation_consistency::<Fr, PoseidonSpongeVar<Fr>>(&poseidon_parameters_for_test());
+    }
 }
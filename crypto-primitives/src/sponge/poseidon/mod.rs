@@ -1,7 +1,8 @@
 use crate::sponge::{
     field_cast, squeeze_field_elements_with_sizes_default_impl, Absorb, CryptographicSponge,
-    DuplexSpongeMode, FieldBasedCryptographicSponge, FieldElementSize, SpongeExt,
+    DuplexSpongeMode, FieldBasedCryptographicSponge, FieldElementSize, Permutation, SpongeExt,
 };
+use crate::Error;
 use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::any::TypeId;
@@ -63,31 +64,31 @@ pub struct PoseidonSponge<F: PrimeField> {
 }
 
 impl<F: PrimeField> PoseidonSponge<F> {
-    fn apply_s_box(&self, state: &mut [F], is_full_round: bool) {
+    fn apply_s_box(parameters: &PoseidonConfig<F>, state: &mut [F], is_full_round: bool) {
         // Full rounds apply the S Box (x^alpha) to every element of state
         if is_full_round {
             for elem in state {
-                *elem = elem.pow(&[self.parameters.alpha]);
+                *elem = elem.pow(&[parameters.alpha]);
             }
         }
         // Partial rounds apply the S Box (x^alpha) to just the first element of state
         else {
-            state[0] = state[0].pow(&[self.parameters.alpha]);
+            state[0] = state[0].pow(&[parameters.alpha]);
         }
     }
 
-    fn apply_ark(&self, state: &mut [F], round_number: usize) {
+    fn apply_ark(parameters: &PoseidonConfig<F>, state: &mut [F], round_number: usize) {
         for (i, state_elem) in state.iter_mut().enumerate() {
-            state_elem.add_assign(&self.parameters.ark[round_number][i]);
+            state_elem.add_assign(&parameters.ark[round_number][i]);
         }
     }
 
-    fn apply_mds(&self, state: &mut [F]) {
+    fn apply_mds(parameters: &PoseidonConfig<F>, state: &mut [F]) {
         let mut new_state = Vec::new();
         for i in 0..state.len() {
             let mut cur = F::zero();
             for (j, state_elem) in state.iter().enumerate() {
-                let term = state_elem.mul(&self.parameters.mds[i][j]);
+                let term = state_elem.mul(&parameters.mds[i][j]);
                 cur.add_assign(&term);
             }
             new_state.push(cur);
@@ -95,29 +96,32 @@ impl<F: PrimeField> PoseidonSponge<F> {
         state.clone_from_slice(&new_state[..state.len()])
     }
 
-    fn permute(&mut self) {
-        let full_rounds_over_2 = self.parameters.full_rounds / 2;
-        let mut state = self.state.clone();
+    /// The Poseidon permutation with `parameters` on a state of `rate + capacity` elements.
+    fn permute_state(parameters: &PoseidonConfig<F>, state: &mut [F]) {
+        let full_rounds_over_2 = parameters.full_rounds / 2;
         for i in 0..full_rounds_over_2 {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, true);
-            self.apply_mds(&mut state);
+            Self::apply_ark(parameters, state, i);
+            Self::apply_s_box(parameters, state, true);
+            Self::apply_mds(parameters, state);
         }
 
-        for i in full_rounds_over_2..(full_rounds_over_2 + self.parameters.partial_rounds) {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, false);
-            self.apply_mds(&mut state);
+        for i in full_rounds_over_2..(full_rounds_over_2 + parameters.partial_rounds) {
+            Self::apply_ark(parameters, state, i);
+            Self::apply_s_box(parameters, state, false);
+            Self::apply_mds(parameters, state);
         }
 
-        for i in (full_rounds_over_2 + self.parameters.partial_rounds)
-            ..(self.parameters.partial_rounds + self.parameters.full_rounds)
+        for i in (full_rounds_over_2 + parameters.partial_rounds)
+            ..(parameters.partial_rounds + parameters.full_rounds)
         {
-            self.apply_ark(&mut state, i);
-            self.apply_s_box(&mut state, true);
-            self.apply_mds(&mut state);
+            Self::apply_ark(parameters, state, i);
+            Self::apply_s_box(parameters, state, true);
+            Self::apply_mds(parameters, state);
         }
-        self.state = state;
+    }
+
+    fn permute(&mut self) {
+        Self::permute_state(&self.parameters, &mut self.state);
     }
 
     // Absorbs everything in elements, this does not end in an absorbtion.
@@ -351,6 +355,22 @@ pub struct PoseidonSpongeState<F: PrimeField> {
     mode: DuplexSpongeMode,
 }
 
+impl<F: PrimeField> Permutation<F> for PoseidonSponge<F> {
+    type Parameters = PoseidonConfig<F>;
+
+    fn width(parameters: &PoseidonConfig<F>) -> usize {
+        parameters.rate + parameters.capacity
+    }
+
+    fn permute(state: &mut [F], parameters: &PoseidonConfig<F>) -> Result<(), Error> {
+        if state.len() != Self::width(parameters) {
+            return Err(Error::IncorrectInputLength(state.len()));
+        }
+        Self::permute_state(parameters, state);
+        Ok(())
+    }
+}
+
 impl<CF: PrimeField> SpongeExt for PoseidonSponge<CF> {
     type State = PoseidonSpongeState<CF>;
 
@@ -1,7 +1,9 @@
 use crate::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
 use crate::sponge::test::Fr;
-use crate::sponge::{Absorb, AbsorbWithLength, CryptographicSponge, FieldBasedCryptographicSponge};
-use crate::{absorb, collect_sponge_bytes, collect_sponge_field_elements};
+use crate::sponge::{
+    Absorb, AbsorbWithLength, CryptographicSponge, FieldBasedCryptographicSponge, Permutation,
+};
+use crate::{absorb, collect_sponge_bytes, collect_sponge_field_elements, Error};
 use ark_ff::{One, PrimeField, UniformRand};
 use ark_std::test_rng;
 
@@ -84,6 +86,21 @@ fn test_squeeze_cast_native() {
     assert_eq!(squeezed1, squeezed2);
 }
 
+#[test]
+fn test_permutation_rejects_wrong_length() {
+    let sponge_param = poseidon_parameters_for_test::<Fr>();
+    let width = PoseidonSponge::<Fr>::width(&sponge_param);
+    let mut state = vec![Fr::one(); width + 1];
+    assert!(matches!(
+        <PoseidonSponge<Fr> as Permutation<Fr>>::permute(&mut state, &sponge_param),
+        Err(Error::IncorrectInputLength(len)) if len == width + 1
+    ));
+    assert!(
+        <PoseidonSponge<Fr> as Permutation<Fr>>::permute(&mut state[..width], &sponge_param)
+            .is_ok()
+    );
+}
+
 #[test]
 fn test_macros() {
     let sponge_param = poseidon_parameters_for_test();